@@ -2,7 +2,9 @@ use pest::Parser as _;
 use pest_derive::Parser;
 use zemen::Zemen;
 
-use crate::calender::{ColumnLocation, DateInfo, DateResult, Event, LineError};
+use crate::calender::{
+    unescape, CalendarKind, ColumnLocation, DateInfo, DateResult, Event, LineError, Recurrence,
+};
 
 fn werh_from_quex(as_str: &str) -> zemen::Werh {
     match as_str {
@@ -32,6 +34,10 @@ impl DateInfo for Zemen {
     fn pretty_print(&self) -> String {
         self.format("MMM D, YYYY")
     }
+
+    fn calendar(&self) -> CalendarKind {
+        CalendarKind::Ethiopian
+    }
 }
 
 #[derive(Parser)]
@@ -63,13 +69,19 @@ pub fn parse_eth_quex(line: &str) -> DateResult<impl DateInfo> {
     match schedule.peek().unwrap().as_rule() {
         Rule::ethiopian_date => {
             let mut date = schedule.next().unwrap().into_inner();
-            let mut message = schedule.next().unwrap().as_str().to_string();
+            let mut message = unescape(schedule.next().unwrap().as_str());
 
             let month = werh_from_quex(date.next().unwrap().as_str());
             let day: u8 = date.next().unwrap().as_str().parse().unwrap();
 
             let year = date.next().unwrap();
 
+            let recurrence = match year.as_rule() {
+                Rule::named_yearly => Recurrence::NamedYearly,
+                Rule::yearly => Recurrence::Yearly,
+                _ => Recurrence::Once,
+            };
+
             let year = if year.as_rule() == Rule::named_yearly {
                 let year = year.into_inner().next().unwrap();
                 let yearn = year.as_str().parse::<i32>().unwrap();
@@ -96,7 +108,7 @@ pub fn parse_eth_quex(line: &str) -> DateResult<impl DateInfo> {
                 Err(e) => return Err(LineError::InvalidValue(e.to_string())),
             };
 
-            Ok(Some(Event::new(date, message)))
+            Ok(Some(Event::new(date, message, recurrence)))
         }
         Rule::recurring_monthly => {
             let day = schedule
@@ -124,9 +136,9 @@ pub fn parse_eth_quex(line: &str) -> DateResult<impl DateInfo> {
                 Err(e) => return Err(LineError::InvalidValue(e.to_string())),
             };
 
-            let message = schedule.next().unwrap().as_str().to_string();
+            let message = unescape(schedule.next().unwrap().as_str());
 
-            Ok(Some(Event::new(date, message)))
+            Ok(Some(Event::new(date, message, Recurrence::Monthly)))
         }
         _ => unreachable!(),
     }
@@ -2,7 +2,9 @@ use pest::Parser as _;
 use pest_derive::Parser;
 use time::Date;
 
-use super::{ColumnLocation, DateInfo, DateResult, Event, LineError};
+use super::{
+    unescape, CalendarKind, ColumnLocation, DateInfo, DateResult, Event, LineError, Recurrence,
+};
 
 pub fn month_from_quex(month: &str) -> time::Month {
     match month {
@@ -30,6 +32,10 @@ impl DateInfo for Date {
     fn pretty_print(&self) -> String {
         format!("{} {}, {}", self.month(), self.day(), self.year())
     }
+
+    fn calendar(&self) -> CalendarKind {
+        CalendarKind::Gregorian
+    }
 }
 
 #[derive(Parser)]
@@ -59,7 +65,7 @@ pub fn parse_gre_quex(line: &str) -> DateResult<impl DateInfo> {
     match schedule.peek().unwrap().as_rule() {
         Rule::gregorian_date => {
             let mut date = schedule.next().unwrap().into_inner();
-            let mut message = schedule.next().unwrap().as_str().to_string();
+            let mut message = unescape(schedule.next().unwrap().as_str());
 
             let month = month_from_quex(date.next().unwrap().as_str());
             let day =
@@ -69,6 +75,12 @@ pub fn parse_gre_quex(line: &str) -> DateResult<impl DateInfo> {
 
             let year = date.next().unwrap();
 
+            let recurrence = match year.as_rule() {
+                Rule::named_yearly => Recurrence::NamedYearly,
+                Rule::yearly => Recurrence::Yearly,
+                _ => Recurrence::Once,
+            };
+
             let year = if year.as_rule() == Rule::named_yearly {
                 let year = year.into_inner().next().unwrap();
                 let yearn: i32 = year.as_str().parse().unwrap();
@@ -94,7 +106,7 @@ pub fn parse_gre_quex(line: &str) -> DateResult<impl DateInfo> {
                 Err(e) => return Err(LineError::InvalidValue(e.to_string())),
             };
 
-            Ok(Some(Event::new(date, message)))
+            Ok(Some(Event::new(date, message, recurrence)))
         }
         Rule::recurring_monthly => {
             let day = schedule
@@ -116,9 +128,9 @@ pub fn parse_gre_quex(line: &str) -> DateResult<impl DateInfo> {
                 Err(e) => return Err(LineError::InvalidValue(e.to_string())),
             };
 
-            let message = schedule.next().unwrap().as_str().to_string();
+            let message = unescape(schedule.next().unwrap().as_str());
 
-            Ok(Some(Event::new(date, message)))
+            Ok(Some(Event::new(date, message, Recurrence::Monthly)))
         }
         _ => unreachable!(),
     }
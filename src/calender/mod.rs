@@ -4,7 +4,7 @@ pub mod eth;
 pub mod gre;
 
 use pest::error::InputLocation;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 use std::{
     fmt::{self},
@@ -18,6 +18,8 @@ pub type DateResult<D> = Result<Option<Event<D>>, LineError>;
 pub enum LineError {
     CantParseInput,
     InvalidValue(String),
+    /// An `@include` directive whose files can't be read.
+    Include(String),
     ParsingError {
         #[serde(skip_serializing)]
         error: String,
@@ -52,19 +54,72 @@ impl serde::Serialize for ColumnLocation {
     }
 }
 
+/// Which calendar a date was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarKind {
+    Gregorian,
+    Ethiopian,
+}
+
+impl fmt::Display for CalendarKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarKind::Gregorian => write!(f, "gregorian"),
+            CalendarKind::Ethiopian => write!(f, "ethiopian"),
+        }
+    }
+}
+
+/// How often an event repeats, as written in the quex line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    /// `jan 1 2025`
+    Once,
+    /// `jan 1 *`
+    Yearly,
+    /// `jan 1 1990*`
+    NamedYearly,
+    /// `d=5` or `q=5`
+    Monthly,
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Once => write!(f, "once"),
+            Recurrence::Yearly => write!(f, "yearly"),
+            Recurrence::NamedYearly => write!(f, "named_yearly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// The text of a message, with its escaped comment markers, `\//`, as `//`.
+fn unescape(message: &str) -> String {
+    message.replace(r"\//", "//")
+}
+
 pub trait DateInfo: fmt::Display + fmt::Debug {
     fn julian_day(&self) -> i32;
     fn pretty_print(&self) -> String;
+    fn calendar(&self) -> CalendarKind;
 }
 
 #[derive(Debug)]
 pub struct Event<C: DateInfo> {
     pub date: C,
     pub message: String,
+    pub recurrence: Recurrence,
 }
 
 impl<C: DateInfo> Event<C> {
-    pub fn new(date: C, message: String) -> Self {
-        Self { date, message }
+    pub fn new(date: C, message: String, recurrence: Recurrence) -> Self {
+        Self {
+            date,
+            message,
+            recurrence,
+        }
     }
 }
@@ -1,12 +1,13 @@
 use std::{
-    io::{stdin, stdout, Read, Write},
+    collections::HashMap,
+    io::{stdin, stdout, IsTerminal, Read, Write},
     path::{Path, PathBuf},
 };
 
 use clap::{Parser, Subcommand, ValueEnum};
 use serde_derive::{Deserialize, Serialize};
 
-use crate::filter::DateWindow;
+use crate::{filter::DateWindow, parser::expand_home, Block, FileFormat, Template};
 
 #[derive(Parser, Debug)]
 pub struct Cli {
@@ -18,9 +19,13 @@ pub struct Cli {
     #[clap(short, long)]
     pub config: Option<PathBuf>,
 
-    /// path to calendar file
+    /// path to calendar file, repeat it to read several calendars, `-` for stdin
     #[clap(short, long)]
-    pub quex: Option<PathBuf>,
+    pub quex: Vec<PathBuf>,
+
+    /// Format of the calendar read from stdin [default: quex]
+    #[clap(long, value_enum)]
+    pub stdin_format: Option<StdinFormat>,
 
     /// command to open calendar file
     #[clap(short, long)]
@@ -38,22 +43,61 @@ pub struct Cli {
     #[clap(long, value_enum)]
     pub format: Option<Format>,
 
+    /// Print events and errors as a single JSON document on stdout (with `--format json`)
+    #[clap(long)]
+    pub envelope: bool,
+
+    /// Template used by `--format template`, e.g. '{iso} {diff:>4} {description}'
+    #[clap(long)]
+    pub template: Option<Template>,
+
+    /// Separate the weeks of `--format agenda`
+    #[clap(long)]
+    pub week_separators: bool,
+
+    /// When to style the output with colors [default: auto]
+    #[clap(long, value_enum)]
+    pub color: Option<ColorChoice>,
+
     /// Filter using a sub string
     #[clap(long)]
     pub filter: Option<String>,
 
+    /// Filter by the headings events are under, using a sub string
+    #[clap(long)]
+    pub category: Option<String>,
+
+    /// Only read the files matching a glob, e.g. 'journal/**/*.md'
+    #[clap(long)]
+    pub include: Vec<String>,
+
+    /// Skip the files and directories matching a glob, e.g. 'node_modules'
+    #[clap(long)]
+    pub exclude: Vec<String>,
+
+    /// Read hidden directories too
+    #[clap(long)]
+    pub hidden: bool,
+
+    /// How many threads read the calendar [default: one per core]
+    #[clap(long)]
+    pub threads: Option<usize>,
+
+    /// Cache the parsed files, so that only the changed ones are parsed again
+    #[clap(long)]
+    pub cache: bool,
+
     /// Filter by window of time
     #[clap(long)]
     pub date_window: Option<DateWindow>,
 
-    /// File format (e.g., md, org)
-    // #[clap(long, value_delimiter = ',', default_value = "md,quex")]
-    #[clap(long, default_value = "md")]
+    /// File format (e.g., txt), read along with the configured ones
+    #[clap(long)]
     pub file_format: Vec<String>,
 
-    /// Block start and end (e.g., ```quex,```)
-    #[clap(long, default_value = "```quex,```")]
-    pub block: Vec<String>,
+    /// Block start and end of each file format (e.g., quex_begin,quex_end), `\,` for a comma
+    #[clap(long)]
+    pub block: Vec<Block>,
 }
 
 #[derive(Debug, PartialEq, ValueEnum, Clone, Serialize, Deserialize)]
@@ -61,6 +105,68 @@ pub struct Cli {
 pub enum Format {
     Json,
     Plain,
+    Csv,
+    Tsv,
+    Template,
+    Jsonl,
+    Agenda,
+    Markdown,
+    Org,
+}
+
+#[derive(Debug, PartialEq, ValueEnum, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorChoice {
+    /// color when printing to a terminal, unless `NO_COLOR` is set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|no_color| no_color.is_empty())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, ValueEnum, Clone, Copy)]
+pub enum StdinFormat {
+    Quex,
+    Md,
+    Org,
+}
+
+impl StdinFormat {
+    /// The extension of the files of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            StdinFormat::Quex => "quex",
+            StdinFormat::Md => "md",
+            StdinFormat::Org => "org",
+        }
+    }
+}
+
+/// Whether something is piped to stdin, `echo 'd=1, rent' | quex`, which is
+/// read unless `stdin = false` is set. A terminal, a redirected file or a
+/// closed stdin isn't read unless `--quex -` is given.
+#[cfg(unix)]
+pub fn stdin_is_piped() -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    std::fs::metadata("/dev/stdin").is_ok_and(|meta| meta.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+pub fn stdin_is_piped() -> bool {
+    false
 }
 
 #[derive(Subcommand, Debug)]
@@ -69,9 +175,14 @@ pub enum Command {
         name = "edit",
         alias = "e",
         about = "edit calendar file",
-        long_about = "open the calendar file with the configured editor, default is nvim"
+        long_about = "open the calendar file with the configured editor, default is nvim. \
+                      When a pattern is given, open the file defining the first event whose \
+                      description contains it, at the line it is defined on"
     )]
-    Edit,
+    Edit {
+        /// open the definition of the event matching this sub string
+        pattern: Option<String>,
+    },
 
     #[clap(name = "week", alias = "w", about = "view schedules file for the week")]
     Week,
@@ -88,15 +199,94 @@ pub enum Command {
 
     #[clap(name = "all", alias = "a", about = "view schedules file for all time")]
     All,
+
+    #[clap(
+        name = "cal",
+        about = "view a month as a calendar grid",
+        long_about = "draw a month as a calendar grid, like cal(1), marking the days that have \
+                      events and listing the events of the month below it"
+    )]
+    Cal {
+        /// month to draw, 1-12 or 1-13 with --ethiopian [default: this month]
+        month: Option<u8>,
+
+        /// year of the month [default: this year]
+        year: Option<i32>,
+
+        /// draw the month in the Ethiopian calendar (needs the `eth` feature)
+        #[clap(long)]
+        ethiopian: bool,
+    },
+
+    #[clap(name = "export", about = "export schedules to a file")]
+    Export {
+        #[clap(subcommand)]
+        export: Export,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Export {
+    #[clap(
+        name = "html",
+        about = "write a static HTML page with month grids and an agenda"
+    )]
+    Html {
+        /// path of the HTML file to write
+        #[clap(long, default_value = "quex.html")]
+        out: PathBuf,
+    },
+}
+
+/// One calendar path, or a list of them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Calendars {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl Calendars {
+    /// The paths, with a leading `~/` expanded to the home directory.
+    pub fn paths(self) -> Vec<PathBuf> {
+        let paths = match self {
+            Calendars::One(path) => vec![path],
+            Calendars::Many(paths) => paths,
+        };
+
+        paths.iter().map(|path| expand_home(path)).collect()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
-    pub calendar: PathBuf,
+    pub calendar: Calendars,
     pub editor: String,
     pub future: Option<i32>,
     pub past: Option<i32>,
     pub format: Option<Format>,
+    pub template: Option<String>,
+    pub envelope: Option<bool>,
+    pub week_separators: Option<bool>,
+    pub color: Option<ColorChoice>,
+    /// Colors keyed by `#tag` or file name, e.g. `"#work" = "blue"`
+    pub colors: Option<HashMap<String, String>>,
+    /// Front matter field daily notes take their description from, e.g. `title`
+    pub note_title: Option<String>,
+    /// Blocks and comment prefix keyed by file extension, e.g. `txt = { start = "quex_begin", end = "quex_end" }`
+    pub file_formats: Option<HashMap<String, FileFormat>>,
+    /// Globs of the files to read, relative to each calendar
+    pub include: Option<Vec<String>>,
+    /// Globs of the files and directories to skip, relative to each calendar
+    pub exclude: Option<Vec<String>>,
+    /// Read hidden directories too
+    pub hidden: Option<bool>,
+    /// How many threads read the calendar, one per core when unset
+    pub threads: Option<usize>,
+    /// Cache the parsed files
+    pub cache: Option<bool>,
+    /// Read a calendar piped to stdin when no `--quex` is given, on by default
+    pub stdin: Option<bool>,
 }
 
 impl Default for Config {
@@ -108,19 +298,39 @@ impl Default for Config {
             .unwrap();
 
         Self {
-            calendar,
+            calendar: Calendars::One(calendar),
             editor: String::from("nvim"),
             future: None,
             past: None,
             format: None,
+            template: None,
+            envelope: None,
+            week_separators: None,
+            color: None,
+            colors: None,
+            note_title: None,
+            file_formats: None,
+            include: None,
+            exclude: None,
+            hidden: None,
+            threads: None,
+            cache: None,
+            stdin: None,
         }
     }
 }
 
-pub fn load_create_config(path: Option<impl AsRef<Path>>) -> Result<Config, String> {
+/// Loads the config file, asking whether to create it when it's missing. When
+/// the question can't be asked, e.g. stdin holds a calendar, the defaults are
+/// used instead.
+pub fn load_create_config(path: Option<impl AsRef<Path>>, prompt: bool) -> Result<Config, String> {
     match path {
         Some(path) => {
             if !path.as_ref().exists() {
+                if !prompt {
+                    return Ok(Config::default());
+                }
+
                 println!("The config path: {:?}, doesn't exist", path.as_ref());
                 print!("Would you like me to create it [y/N]: ");
                 stdout().flush().expect("Something wrong with stdout");
@@ -142,6 +352,10 @@ pub fn load_create_config(path: Option<impl AsRef<Path>>) -> Result<Config, Stri
                 .expect("Error finding config directory");
 
             if !config_path.exists() {
+                if !prompt {
+                    return Ok(Config::default());
+                }
+
                 println!("The config path: {config_path:?}, doesn't exist");
                 print!("Would you like me to create it [y/N]: ");
                 stdout().flush().expect("Something wrong with stdout");
@@ -163,6 +377,24 @@ pub fn load_create_config(path: Option<impl AsRef<Path>>) -> Result<Config, Stri
 
 #[cfg(test)]
 mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::Calendars;
+
     #[test]
     fn test_config_file() {}
+
+    #[test]
+    fn calendar_paths() {
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        let calendars = Calendars::Many(vec!["~/notes/".into(), "/work/~/a.quex".into()]);
+
+        assert_eq!(
+            calendars.paths(),
+            [
+                home.join("notes"),
+                Path::new("/work/~/a.quex").to_path_buf()
+            ]
+        );
+    }
 }
@@ -1,14 +1,14 @@
 use std::fmt::Debug;
-use std::path::PathBuf;
-
-use serde_derive::Serialize;
+use std::path::{Path, PathBuf};
 
 use crate::calender::LineError;
 
-#[derive(Serialize, Debug)]
+#[derive(Debug)]
 pub struct Error {
     path: PathBuf,
     errors: Vec<ValueError>,
+    /// Why the file couldn't be read, its lines are missing from the errors.
+    io_error: Option<String>,
 }
 
 impl Error {
@@ -16,6 +16,7 @@ impl Error {
         Self {
             path,
             errors: vec![],
+            io_error: None,
         }
     }
 
@@ -23,11 +24,30 @@ impl Error {
         self.errors.push(error);
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn errors(&self) -> &[ValueError] {
+        &self.errors
+    }
+
+    pub fn io_error(&self) -> Option<&str> {
+        self.io_error.as_deref()
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.errors.is_empty()
+        self.errors.is_empty() && self.io_error.is_none()
     }
 
     pub fn format(self) -> String {
+        if let Some(io_error) = &self.io_error {
+            return format!(
+                "error: -> {}\n  |\n  = note: can't read: {io_error}\n\n\n",
+                self.path.to_string_lossy()
+            );
+        }
+
         let cap = self.errors.len();
         self.errors
             .into_iter()
@@ -41,7 +61,7 @@ impl Error {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Debug)]
 pub struct ValueError {
     line_error: LineError,
     line_number: usize,
@@ -57,10 +77,25 @@ impl ValueError {
         }
     }
 
+    pub fn line_error(&self) -> &LineError {
+        &self.line_error
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
     pub fn format(self) -> String {
         match self.line_error {
             LineError::CantParseInput => cant_parse_input(self.line_number, self.line),
             LineError::InvalidValue(m) => invalid_value(m, self.line_number, self.line),
+            LineError::Include(m) => {
+                invalid_value(format!("can't include: {m}"), self.line_number, self.line)
+            }
             LineError::ParsingError { error, .. } => format!("{error}\n\n"),
         }
     }
@@ -118,6 +153,15 @@ pub mod io {
         }
     }
 
+    impl From<FileError> for super::Error {
+        fn from(error: FileError) -> Self {
+            Self {
+                io_error: Some(error.source.to_string()),
+                ..Self::new(error.file)
+            }
+        }
+    }
+
     impl fmt::Debug for FileError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             write!(f, "{} ", self)?;
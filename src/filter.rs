@@ -4,6 +4,7 @@ pub enum FilterOption {
     Ranged { future: i32, past: i32 },
     All,
     SubStr(String),
+    Category(String),
     DateWindow(DateWindow),
 }
 
@@ -15,9 +16,51 @@ impl FilterOption {
     pub fn new_sub_str(sub_str: String) -> Self {
         Self::SubStr(sub_str)
     }
+
+    pub fn new_category(category: String) -> Self {
+        Self::Category(category)
+    }
     pub fn date_window(dw: DateWindow) -> Self {
         Self::DateWindow(dw)
     }
+
+    /// The julian day numbers this filter lets through.
+    pub fn window(&self) -> Window {
+        let today = time::OffsetDateTime::now_utc().to_julian_day();
+
+        match self {
+            FilterOption::Ranged { future, past } => Window {
+                begin: Some(today - past + 1),
+                end: Some(today + future - 1),
+            },
+            FilterOption::DateWindow(DateWindow { begin, end }) => Window {
+                begin: Some(*begin),
+                end: Some(*end),
+            },
+            FilterOption::All | FilterOption::SubStr(_) | FilterOption::Category(_) => {
+                Window::default()
+            }
+        }
+    }
+}
+
+/// An inclusive range of julian day numbers, `None` being unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Window {
+    pub begin: Option<i32>,
+    pub end: Option<i32>,
+}
+
+impl Window {
+    fn intersect(self, other: Window) -> Window {
+        Window {
+            begin: self.begin.max(other.begin),
+            end: match (self.end, other.end) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
 }
 
 fn filter_schedules(mut schedules: Schedules, filter_options: Option<FilterOption>) -> Schedules {
@@ -37,6 +80,14 @@ fn filter_schedules(mut schedules: Schedules, filter_options: Option<FilterOptio
                 .into_iter()
                 .filter(|sch| sch.description.contains(sub_str.as_str()))
                 .collect(),
+            FilterOption::Category(category) => schedules
+                .into_iter()
+                .filter(|sch| {
+                    sch.category
+                        .as_deref()
+                        .is_some_and(|c| c.contains(category.as_str()))
+                })
+                .collect(),
 
             FilterOption::DateWindow(DateWindow { begin, end }) => schedules
                 .into_iter()
@@ -67,6 +118,15 @@ pub fn command_to_filter(command: Option<&Command>) -> Option<FilterOption> {
     }
 }
 
+/// The window of dates that make it through every filter of the pipeline.
+pub fn pipeline_window(pipeline: &[Option<FilterOption>]) -> Window {
+    pipeline
+        .iter()
+        .flatten()
+        .map(FilterOption::window)
+        .fold(Window::default(), Window::intersect)
+}
+
 pub fn filter_pipeline(schedules: Schedules, pipeline: Vec<Option<FilterOption>>) -> Schedules {
     pipeline.into_iter().fold(schedules, filter_schedules)
 }
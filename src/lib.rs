@@ -1,52 +1,47 @@
-use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
-    process::Command,
-};
+use std::{path::PathBuf, process::Command};
 
 pub use crate::parser::schedule::Schedule;
 pub use cli::Format;
-pub use parser::walker::{QErrors, Schedules};
+pub use parser::{
+    block::{Block, FileFormat},
+    cache::cache_file,
+    walker::{QErrors, Schedules, WalkOptions, STDIN},
+};
+pub use view::{
+    export_html,
+    grid::MonthGrid,
+    style::{Color, Palette},
+    template::Template,
+    view_calendar, view_envelope, view_parse_errors, view_schedules, ViewOptions,
+};
 
 pub mod cli;
 mod error;
 pub mod filter;
 mod parser;
+mod view;
 
 pub mod calender;
 
-pub fn get_schedules(path: PathBuf, file_format: HashMap<String, String>) -> (Schedules, QErrors) {
-    parser::walker::walk_dir(path, &file_format).unwrap()
+pub fn get_schedules(roots: &[PathBuf], options: &WalkOptions) -> (Schedules, QErrors) {
+    parser::walker::walk_dirs(roots, options)
 }
 
-
-pub fn view_schedules(schedules: Schedules, format: &Format) {
-    match format {
-        Format::Json => {
-            let json = serde_json::to_string(&schedules).unwrap();
-            println!("{}", json);
-        }
-        Format::Plain => schedules.iter().for_each(|sch| match sch.diff {
-            0 => println!("Today; {}", sch.description),
-            1 => println!("Tomorrow; {}", sch.description),
-            -1 => println!("Yesterday; {}", sch.description),
-            _ => println!("{}; {}", sch.date, sch.description),
-        }),
-    }
+pub fn edit_schedules(roots: &[PathBuf], editor: String) {
+    let paths = roots.iter().filter(|root| root.as_os_str() != STDIN);
+    Command::new(editor).args(paths).status().unwrap();
 }
 
-pub fn view_parse_errors(errors: QErrors, format: &Format) {
-    match format {
-        Format::Json => {
-            let json = serde_json::to_string(&errors).unwrap();
-            eprintln!("{}", json);
-        }
-        Format::Plain => errors
-            .into_iter()
-            .for_each(|err| eprint!("{}", err.format())),
+/// Opens the file a schedule was read from, with the cursor on its line.
+pub fn edit_schedule(schedule: &Schedule, editor: String) {
+    let Some(path) = &schedule.source_path else {
+        return;
+    };
+
+    let mut command = Command::new(editor);
+    if let Some(line_number) = schedule.line_number {
+        command.arg(format!("+{line_number}"));
     }
-}
 
-pub fn edit_schedules(path: &Path, editor: String) {
-    Command::new(editor).arg(path).status().unwrap();
+    command.arg(path).status().unwrap();
 }
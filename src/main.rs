@@ -1,78 +1,240 @@
-use std::{collections::HashMap, error::Error};
+use std::{collections::HashMap, io::IsTerminal};
 
 use clap::Parser;
 use quex::{
-    cli::{self, Cli, Command},
+    calender::CalendarKind,
+    cli::{self, Cli, ColorChoice, Command, Export},
     filter::{self, FilterOption},
+    Color, FileFormat, Palette, ViewOptions, WalkOptions,
 };
 
 fn main() {
     let Cli {
         config,
         quex,
+        stdin_format,
         editor,
         command,
         future,
         past,
         format,
+        envelope,
+        template,
+        week_separators,
+        color,
         filter: filter_str,
+        category,
         date_window,
         file_format,
         block,
+        include,
+        exclude,
+        hidden,
+        threads,
+        cache,
     } = Cli::parse();
 
     if file_format.len() != block.len() {
         eprintln!("Error: The number of file formats and blocks must match.");
-        return;
+        std::process::exit(1);
     }
 
-    let file_format: Result<HashMap<String, String>, Box<dyn Error>> = file_format
-        .into_iter()
-        .zip(block.into_iter())
-        .map(|(ff, bb)| {
-            let bparts: Vec<&str> = bb.split(',').collect();
-
-            if bparts.len() != 2 {
-                return Err(format!(
-                    "Block '{}' must contain a `start` and `end` separated by a comma.",
-                    bb
-                )
-                .into());
-            }
-
-            Ok((ff, bb))
-        })
-        .collect();
+    // the answer to creating a missing config file can only come from a
+    // terminal, and not one a calendar is typed into
+    let stdin_input = quex.iter().any(|path| path.as_os_str() == quex::STDIN);
+    let prompt = !stdin_input && std::io::stdin().is_terminal();
+    let app_config = match cli::load_create_config(config, prompt) {
+        Ok(app_config) => app_config,
+        Err(e) => {
+            eprintln!("Error loading {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // the built-in formats, overridden by the config file and then by the cli
+    let mut file_formats = FileFormat::defaults();
+    file_formats.extend(app_config.file_formats.unwrap_or_default());
+
+    let mut cli_formats: HashMap<String, FileFormat> = HashMap::new();
+    for (extension, block) in file_format.into_iter().zip(block) {
+        cli_formats.entry(extension).or_default().blocks.push(block);
+    }
+    file_formats.extend(cli_formats);
 
-    let file_format = file_format.unwrap();
+    for (extension, format) in &file_formats {
+        if let Err(e) = format.validate() {
+            eprintln!("Error: invalid block for `{extension}` files: {e}");
+            std::process::exit(1);
+        }
+    }
 
-    let app_config = cli::load_create_config(config).expect("Error loading config file");
+    // the globs of the cli are added to the ones of the config file
+    let mut include_globs = app_config.include.unwrap_or_default();
+    include_globs.extend(include);
+    let mut exclude_globs = app_config.exclude.unwrap_or_default();
+    exclude_globs.extend(exclude);
+
+    let walk_options = WalkOptions {
+        file_formats,
+        note_title: app_config.note_title,
+        include: include_globs,
+        exclude: exclude_globs,
+        hidden: hidden || app_config.hidden.unwrap_or(false),
+        threads: threads.or(app_config.threads),
+        cache: match cache || app_config.cache.unwrap_or(false) {
+            true => quex::cache_file(),
+            false => None,
+        },
+        stdin_format: stdin_format
+            .unwrap_or(cli::StdinFormat::Quex)
+            .extension()
+            .to_string(),
+    };
+
+    if let Err(e) = walk_options.validate() {
+        eprintln!("Error: invalid glob: {e}");
+        std::process::exit(1);
+    }
 
-    let quex_path = quex.unwrap_or(app_config.calendar);
+    // a calendar piped in is read instead of the configured one, unless
+    // `stdin = false`
+    let read_piped = app_config.stdin.unwrap_or(true) && cli::stdin_is_piped();
+    let roots = match (quex.is_empty(), read_piped) {
+        (true, true) => vec![quex::STDIN.into()],
+        (true, false) => app_config.calendar.paths(),
+        (false, _) => quex,
+    };
     let editor = editor.unwrap_or(std::env::var("EDITOR").unwrap_or(app_config.editor));
     let format = format.unwrap_or(app_config.format.unwrap_or(quex::Format::Plain));
 
+    let template = match (template, app_config.template) {
+        (Some(template), _) => template,
+        (None, Some(template)) => match template.parse() {
+            Ok(template) => template,
+            Err(e) => {
+                eprintln!("Error: invalid template in config file: {e}");
+                std::process::exit(1);
+            }
+        },
+        (None, None) => quex::Template::default(),
+    };
+    let colors: Result<HashMap<String, Color>, String> = app_config
+        .colors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, color)| Ok((key, color.parse()?)))
+        .collect();
+
+    let colors = match colors {
+        Ok(colors) => colors,
+        Err(e) => {
+            eprintln!("Error: invalid colors in config file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let color = color.or(app_config.color).unwrap_or(ColorChoice::Auto);
+    let palette = Palette::new(color.enabled(), colors);
+
+    let week_separators = week_separators || app_config.week_separators.unwrap_or(false);
+
+    let view_options = ViewOptions {
+        template,
+        palette,
+        week_separators,
+    };
+    let envelope = envelope || app_config.envelope.unwrap_or(false);
+
     let future = future.unwrap_or(app_config.future.unwrap_or(14));
     let past = past.unwrap_or(app_config.past.unwrap_or(3));
 
     // Commands
-    if let Some(Command::Edit) = &command {
-        quex::edit_schedules(quex_path.as_path(), editor);
+    if let Some(Command::Edit { pattern }) = &command {
+        match pattern {
+            Some(pattern) => {
+                let (schedules, _) = quex::get_schedules(&roots, &walk_options);
+
+                match schedules
+                    .iter()
+                    .find(|sch| sch.description.contains(pattern.as_str()))
+                {
+                    Some(schedule) => quex::edit_schedule(schedule, editor),
+                    None => {
+                        eprintln!("Error: no event matches '{pattern}'");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            None => quex::edit_schedules(&roots, editor),
+        }
     }
 
     // Filtering options
-    let (schedules, parse_errors) = quex::get_schedules(quex_path, file_format);
+    let (schedules, parse_errors) = quex::get_schedules(&roots, &walk_options);
+
+    if let Some(Command::Cal {
+        month,
+        year,
+        ethiopian,
+    }) = &command
+    {
+        let calendar = match ethiopian {
+            true => CalendarKind::Ethiopian,
+            false => CalendarKind::Gregorian,
+        };
+
+        let grid = match quex::MonthGrid::new(calendar, *year, *month) {
+            Ok(grid) => grid,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        let sub_str_filter = filter_str.map(FilterOption::new_sub_str);
+        let category_filter = category.map(FilterOption::new_category);
+        let schedules = filter::filter_pipeline(schedules, vec![sub_str_filter, category_filter]);
+
+        quex::view_calendar(&grid, schedules, &view_options);
+        quex::view_parse_errors(parse_errors, &format);
+        return;
+    }
 
     let date_window_filter = date_window.map(FilterOption::date_window);
     let range_filter = Some(FilterOption::new_ranged(future, past));
     let command_filter = filter::command_to_filter(command.as_ref()).or(range_filter);
     let sub_str_filter = filter_str.map(FilterOption::new_sub_str);
+    let category_filter = category.map(FilterOption::new_category);
 
-    let pipeline = vec![command_filter, sub_str_filter, date_window_filter];
+    let pipeline = vec![
+        command_filter,
+        sub_str_filter,
+        category_filter,
+        date_window_filter,
+    ];
 
+    let window = filter::pipeline_window(&pipeline);
     let schedules = filter::filter_pipeline(schedules, pipeline);
 
+    if let Some(Command::Export {
+        export: Export::Html { out },
+    }) = &command
+    {
+        if let Err(e) = quex::export_html(&schedules, out) {
+            eprintln!("Error: can't write {out:?}: {e}");
+            quex::view_parse_errors(parse_errors, &format);
+            std::process::exit(1);
+        }
+
+        quex::view_parse_errors(parse_errors, &format);
+        return;
+    }
+
     // print the schedules
-    quex::view_schedules(schedules, &format);
-    quex::view_parse_errors(parse_errors, &format);
+    if envelope && format == quex::Format::Json {
+        quex::view_envelope(schedules, parse_errors, window);
+    } else {
+        quex::view_schedules(schedules, &format, &view_options);
+        quex::view_parse_errors(parse_errors, &format);
+    }
 }
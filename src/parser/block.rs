@@ -0,0 +1,268 @@
+// Extraction of quex lines from files of a configured format, e.g.
+//
+// [file_formats.rs]
+// blocks = [{ start = "/* quex", end = "*/" }]
+// comment = "// quex:"
+//
+// A line between the start and end of any of the blocks is a quex line, as is
+// the rest of a line after the comment prefix. Delimiters are matched as sub
+// strings of the line, or as regular expressions when `regex` is set.
+
+use std::{collections::HashMap, str::FromStr};
+
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+
+use super::walker::QuexLine;
+
+/// The delimiters of a quex block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub start: String,
+    pub end: String,
+    /// Match the delimiters as regular expressions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub regex: bool,
+}
+
+impl Block {
+    pub fn new(start: impl Into<String>, end: impl Into<String>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            regex: false,
+        }
+    }
+
+    fn matcher(&self) -> Result<(Delimiter, Delimiter), regex::Error> {
+        Ok((
+            Delimiter::new(&self.start, self.regex)?,
+            Delimiter::new(&self.end, self.regex)?,
+        ))
+    }
+}
+
+/// `start,end`, as given to `--block`. A comma within a delimiter is escaped
+/// as `\,`.
+impl FromStr for Block {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = vec![String::new()];
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&',') => {
+                    parts.last_mut().unwrap().push(',');
+                    chars.next();
+                }
+                ',' => parts.push(String::new()),
+                c => parts.last_mut().unwrap().push(c),
+            }
+        }
+
+        match &parts[..] {
+            [start, end] => Ok(Block::new(start.trim(), end.trim())),
+            _ => Err(format!(
+                "Block '{s}' must contain a `start` and `end` separated by a comma."
+            )),
+        }
+    }
+}
+
+/// How quex lines are embedded in the files of an extension.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "FileFormatSpec")]
+pub struct FileFormat {
+    pub blocks: Vec<Block>,
+    /// The prefix of a comment holding a quex line, e.g. `// quex:`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// A file format as written in the config file, a single block can be given
+/// in place of the whole format, `txt = { start = "quex_begin", end = "quex_end" }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FileFormatSpec {
+    Block(Block),
+    Format {
+        #[serde(default)]
+        blocks: Vec<Block>,
+        comment: Option<String>,
+    },
+}
+
+impl From<FileFormatSpec> for FileFormat {
+    fn from(spec: FileFormatSpec) -> Self {
+        match spec {
+            FileFormatSpec::Block(block) => FileFormat::from(block),
+            FileFormatSpec::Format { blocks, comment } => FileFormat { blocks, comment },
+        }
+    }
+}
+
+impl From<Block> for FileFormat {
+    fn from(block: Block) -> Self {
+        FileFormat {
+            blocks: vec![block],
+            comment: None,
+        }
+    }
+}
+
+impl FileFormat {
+    /// The formats read when none are configured, markdown and org. `.quex`
+    /// files are always read.
+    pub fn defaults() -> HashMap<String, FileFormat> {
+        HashMap::from([
+            ("md".to_string(), Block::new("```quex", "```").into()),
+            ("markdown".to_string(), Block::new("```quex", "```").into()),
+            (
+                "org".to_string(),
+                Block::new("#+begin_src quex", "#+end_src").into(),
+            ),
+        ])
+    }
+
+    /// Checks the regular expressions of the blocks.
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        self.blocks
+            .iter()
+            .try_for_each(|block| block.matcher().map(|_| ()))
+    }
+}
+
+enum Delimiter {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Delimiter {
+    fn new(delimiter: &str, regex: bool) -> Result<Self, regex::Error> {
+        match regex {
+            true => Regex::new(delimiter).map(Delimiter::Regex),
+            false => Ok(Delimiter::Text(delimiter.to_string())),
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            Delimiter::Text(text) => line.trim().contains(text.as_str()),
+            Delimiter::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// The lines of every block and comment in `source`, with their line number.
+pub fn quex_lines(source: &str, blocks: &[&Block], comment: Option<&str>) -> Vec<QuexLine> {
+    let matchers: Vec<_> = blocks
+        .iter()
+        .filter_map(|block| block.matcher().ok())
+        .collect();
+
+    let mut lines = vec![];
+    // the end delimiter of the block the line is in
+    let mut end: Option<&Delimiter> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+
+        if let Some(delimiter) = end {
+            match delimiter.matches(line) {
+                true => end = None,
+                false => lines.push(QuexLine {
+                    line_number,
+                    text: line.to_string(),
+                    source: None,
+                    category: None,
+                }),
+            }
+            continue;
+        }
+
+        if let Some((_, delimiter)) = matchers.iter().find(|(start, _)| start.matches(line)) {
+            end = Some(delimiter);
+            continue;
+        }
+
+        let quex = comment
+            .filter(|prefix| !prefix.is_empty())
+            .and_then(|prefix| line.find(prefix).map(|i| &line[i + prefix.len()..]));
+
+        if let Some(text) = quex {
+            lines.push(QuexLine {
+                line_number,
+                text: text.trim().to_string(),
+                source: None,
+                category: None,
+            });
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quex_lines, Block, FileFormat};
+
+    #[test]
+    fn cli_blocks() {
+        assert_eq!(
+            "quex_begin,quex_end".parse(),
+            Ok(Block::new("quex_begin", "quex_end"))
+        );
+        assert_eq!(r"{quex\,,\,}".parse(), Ok(Block::new("{quex,", ",}")));
+        assert!("quex_begin".parse::<Block>().is_err());
+        assert!("a,b,c".parse::<Block>().is_err());
+    }
+
+    #[test]
+    fn config_formats() {
+        let config = r#"
+txt = { start = "quex_begin", end = "quex_end" }
+
+[rs]
+comment = "// quex:"
+blocks = [{ start = '^\s*/\*\s*quex', end = '\*/', regex = true }]
+"#;
+
+        let formats: std::collections::HashMap<String, FileFormat> =
+            toml::from_str(config).unwrap();
+
+        assert_eq!(formats["txt"], Block::new("quex_begin", "quex_end").into());
+        assert_eq!(formats["rs"].comment.as_deref(), Some("// quex:"));
+        assert!(formats["rs"].blocks[0].regex);
+    }
+
+    #[test]
+    fn blocks_and_comments() {
+        let source = r"fn main() {
+    // quex: jan 1 2025, revisit main
+    /* QUEX
+    jan 2 2025, two
+    */
+    let block = 1; // quex: jan 3 2025, three
+}
+";
+
+        let mut block = Block::new(r"(?i)/\*\s*quex", r"\*/");
+        block.regex = true;
+
+        let lines: Vec<_> = quex_lines(source, &[&block], Some("// quex:"))
+            .into_iter()
+            .map(|line| (line.line_number, line.text))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                (2, "jan 1 2025, revisit main".to_string()),
+                (4, "    jan 2 2025, two".to_string()),
+                (6, "jan 3 2025, three".to_string()),
+            ]
+        );
+    }
+}
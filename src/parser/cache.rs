@@ -0,0 +1,254 @@
+// An on-disk cache of the parsed files, enabled with `--cache`.
+//
+// A file is looked up by its canonical path and modification time, and when the time
+// changed, by a hash of its content. Events on a fixed day are stored parsed.
+// Recurring events, and lines that didn't parse, depend on today so they are
+// stored as their quex line and parsed again on every run.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    walker::{QuexLine, WalkOptions},
+    Schedule,
+};
+use crate::calender::{CalendarKind, Recurrence};
+
+/// Bumped whenever the layout of the cache changes.
+const VERSION: u32 = 4;
+
+/// The cache file, in the cache directory of the platform.
+pub fn cache_file() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "quex").map(|dirs| dirs.cache_dir().join("cache.json"))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    version: u32,
+    /// A hash of the settings the files were read with, the cache is dropped
+    /// when they change.
+    settings: u64,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    /// Seconds and nanoseconds since the unix epoch.
+    modified: (u64, u32),
+    hash: u64,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Entry {
+    /// An event on a fixed day, as parsed.
+    Fixed {
+        line_number: usize,
+        description: String,
+        julian_day_number: i32,
+        date: String,
+        calendar: CalendarKind,
+        raw: String,
+        category: Option<String>,
+    },
+    /// A line that is parsed on every run.
+    Line {
+        line_number: usize,
+        text: String,
+        source: Option<String>,
+        category: Option<String>,
+    },
+}
+
+impl Entry {
+    pub fn new(line: QuexLine) -> Self {
+        match super::parse_line(line.text.trim()) {
+            Ok(sch) if sch.recurrence == Recurrence::Once => Entry::Fixed {
+                line_number: line.line_number,
+                description: sch.description,
+                julian_day_number: sch.julian_day_number,
+                date: sch.date,
+                calendar: sch.calendar,
+                raw: line.source.unwrap_or(sch.raw),
+                category: line.category,
+            },
+            _ => Entry::Line {
+                line_number: line.line_number,
+                text: line.text,
+                source: line.source,
+                category: line.category,
+            },
+        }
+    }
+}
+
+/// A fixed entry as a schedule of `path`, `None` for a line to parse.
+pub fn fixed_schedule(entry: &Entry, path: &Path, today: i32) -> Option<Schedule> {
+    let Entry::Fixed {
+        line_number,
+        description,
+        julian_day_number,
+        date,
+        calendar,
+        raw,
+        category,
+    } = entry
+    else {
+        return None;
+    };
+
+    Some(Schedule {
+        description: description.clone(),
+        julian_day_number: *julian_day_number,
+        diff: julian_day_number - today,
+        date: date.clone(),
+        source_path: Some(path.to_path_buf()),
+        line_number: Some(*line_number),
+        calendar: *calendar,
+        recurrence: Recurrence::Once,
+        raw: raw.clone(),
+        category: category.clone(),
+    })
+}
+
+impl Cache {
+    /// The cache at `path`, empty when it's missing, unreadable or was written
+    /// with other settings.
+    pub fn load(path: &Path, options: &WalkOptions) -> Self {
+        let settings = settings(options);
+
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Cache>(&bytes).ok())
+            .filter(|cache| cache.version == VERSION && cache.settings == settings)
+            .unwrap_or(Cache {
+                version: VERSION,
+                settings,
+                files: HashMap::new(),
+            })
+    }
+
+    /// The entries of a file, if it didn't change since it was cached.
+    pub fn get(&self, path: &Path, modified: SystemTime) -> Option<&CachedFile> {
+        self.files
+            .get(path)
+            .filter(|file| file.modified == timestamp(modified))
+    }
+
+    /// The entries of a file whose content hashes to `hash`.
+    pub fn get_by_hash(&self, path: &Path, hash: u64) -> Option<&CachedFile> {
+        self.files.get(path).filter(|file| file.hash == hash)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, file: CachedFile) {
+        self.files.insert(path, file);
+    }
+
+    /// Writes the cache, the files that no longer exist are left out.
+    pub fn save(mut self, path: &Path) -> std::io::Result<()> {
+        self.files.retain(|file, _| file.exists());
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // written aside first, so that a concurrent run never reads half a cache
+        let temporary = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&temporary, serde_json::to_vec(&self)?)?;
+        fs::rename(temporary, path)
+    }
+}
+
+impl CachedFile {
+    pub fn new(modified: SystemTime, hash: u64, entries: Vec<Entry>) -> Self {
+        Self {
+            modified: timestamp(modified),
+            hash,
+            entries,
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn touched(&self, modified: SystemTime) -> Self {
+        Self {
+            modified: timestamp(modified),
+            ..self.clone()
+        }
+    }
+}
+
+fn timestamp(time: SystemTime) -> (u64, u32) {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    (since_epoch.as_secs(), since_epoch.subsec_nanos())
+}
+
+/// A hash of everything that changes how a file is read.
+fn settings(options: &WalkOptions) -> u64 {
+    let file_formats: BTreeMap<_, _> = options.file_formats.iter().collect();
+
+    fnv1a(
+        format!(
+            "{file_formats:?} {:?} {}",
+            options.note_title,
+            cfg!(feature = "eth")
+        )
+        .as_bytes(),
+    )
+}
+
+/// The 64 bit FNV-1a hash.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixed_schedule, fnv1a, Entry};
+    use crate::parser::walker::QuexLine;
+
+    #[test]
+    fn fnv() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn entries() {
+        let line = |text: &str| QuexLine {
+            line_number: 3,
+            text: text.to_string(),
+            source: None,
+            category: None,
+        };
+
+        let fixed = Entry::new(line("jan 1 2025, new year"));
+        let sch = fixed_schedule(&fixed, "a.quex".as_ref(), 2460676).unwrap();
+        assert_eq!(sch.julian_day_number, 2460677);
+        assert_eq!(sch.diff, 1);
+        assert_eq!(sch.raw, "jan 1 2025, new year");
+
+        // recurring events and errors are parsed on every run
+        assert!(matches!(
+            Entry::new(line("jan 1 *, new year")),
+            Entry::Line { .. }
+        ));
+        assert!(matches!(Entry::new(line("d=5, rent")), Entry::Line { .. }));
+        assert!(matches!(Entry::new(line("nonsense")), Entry::Line { .. }));
+    }
+}
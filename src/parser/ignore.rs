@@ -0,0 +1,109 @@
+// Which files of a calendar are read.
+//
+// The `include` and `exclude` globs of the config are relative to each
+// calendar root, and the patterns of a `.quexignore` file are relative to its
+// directory. As in a `.gitignore`, a pattern without a slash matches a name at
+// any depth, `node_modules`, a pattern with one matches a path, `/journal/*.md`
+// or `attachments/**`, and a trailing slash matches directories only.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern, PatternError};
+
+pub const IGNORE_FILE: &str = ".quexignore";
+
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A glob matched against the paths under `base`.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    base: PathBuf,
+    pattern: Pattern,
+    /// Matched against the whole path under `base` rather than the name.
+    anchored: bool,
+    dir_only: bool,
+}
+
+impl Rule {
+    pub fn new(base: &Path, glob: &str) -> Result<Self, PatternError> {
+        let dir_only = glob.ends_with('/');
+        let glob = glob.trim_end_matches('/');
+        let anchored = glob.contains('/');
+
+        Ok(Self {
+            base: base.to_path_buf(),
+            pattern: Pattern::new(glob.trim_start_matches('/'))?,
+            anchored,
+            dir_only,
+        })
+    }
+
+    pub fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        match self.anchored {
+            true => self.pattern.matches_path_with(relative, OPTIONS),
+            false => relative
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| self.pattern.matches_with(name, OPTIONS)),
+        }
+    }
+}
+
+/// The rules of the `.quexignore` file of `dir`, if it has one. Blank lines
+/// and lines starting with `#` are skipped, as are invalid patterns.
+pub fn ignore_file(dir: &Path) -> Vec<Rule> {
+    let Ok(source) = fs::read_to_string(dir.join(IGNORE_FILE)) else {
+        return vec![];
+    };
+
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| Rule::new(dir, line).ok())
+        .collect()
+}
+
+pub fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Rule;
+
+    #[test]
+    fn rules() {
+        let root = Path::new("/notes");
+        let rule = |glob| Rule::new(root, glob).unwrap();
+
+        assert!(rule("node_modules").matches(Path::new("/notes/web/node_modules"), true));
+        assert!(rule("*.md").matches(Path::new("/notes/a/b.md"), false));
+        assert!(rule("/journal/*.md").matches(Path::new("/notes/journal/a.md"), false));
+        assert!(!rule("/journal/*.md").matches(Path::new("/notes/journal/2025/a.md"), false));
+        assert!(rule("journal/**/*.md").matches(Path::new("/notes/journal/2025/a.md"), false));
+        assert!(rule("journal/**/*.md").matches(Path::new("/notes/journal/a.md"), false));
+        assert!(rule("attachments/").matches(Path::new("/notes/attachments"), true));
+        assert!(!rule("attachments/").matches(Path::new("/notes/attachments"), false));
+        assert!(!rule("*.md").matches(Path::new("/other/a.md"), false));
+    }
+}
@@ -0,0 +1,108 @@
+// The `@include` directive of `.quex` files and quex blocks, e.g.
+//
+// @include ../shared/holidays.quex
+// @include ~/calendars/team/*.quex
+//
+// The path is relative to the directory of the including file, and a glob
+// includes every file of a known format it matches, in order of their path.
+
+use std::path::{Path, PathBuf};
+
+const DIRECTIVE: &str = "@include";
+
+/// The path of an include directive, `None` for any other line.
+pub fn directive(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix(DIRECTIVE)?;
+
+    match rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        true => Some(rest.trim()),
+        false => None,
+    }
+}
+
+/// Whether the included path is a glob rather than a single file.
+pub fn is_glob(target: &str) -> bool {
+    target.contains(['*', '?', '['])
+}
+
+/// The files an include directive of a file in `dir` refers to.
+pub fn resolve(dir: &Path, target: &str) -> Result<Vec<PathBuf>, String> {
+    if target.is_empty() {
+        return Err("missing the path to include".to_string());
+    }
+    let glob = is_glob(target);
+
+    let path = dir.join(super::expand_home(Path::new(target)));
+
+    if !glob {
+        return Ok(vec![path]);
+    }
+
+    let paths = glob::glob(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+    let files: Vec<PathBuf> = paths
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+
+    match files.is_empty() {
+        true => Err(format!("no file matches `{}`", path.display())),
+        false => Ok(files),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{directive, resolve};
+
+    #[test]
+    fn directives() {
+        assert_eq!(directive("@include holidays.quex"), Some("holidays.quex"));
+        assert_eq!(
+            directive("  @include  ../shared/*.quex "),
+            Some("../shared/*.quex")
+        );
+        assert_eq!(directive("@include"), Some(""));
+        assert_eq!(directive("@included.quex"), None);
+        assert_eq!(directive("jan 1 2025, @include"), None);
+    }
+
+    #[test]
+    fn resolves() {
+        let dir = std::env::temp_dir().join(format!("quex-resolve-{}", std::process::id()));
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::create_dir_all(dir.join("team")).unwrap();
+        for file in ["a.quex", "b.quex", "notes.md"] {
+            fs::write(dir.join("shared").join(file), "").unwrap();
+        }
+
+        let team = dir.join("team");
+        assert_eq!(
+            resolve(&team, "../shared/a.quex"),
+            Ok(vec![team.join("../shared/a.quex")])
+        );
+        assert_eq!(
+            resolve(&team, "../shared/*.quex"),
+            Ok(vec![
+                team.join("../shared/a.quex"),
+                team.join("../shared/b.quex")
+            ])
+        );
+        // a missing file is reported when it's read
+        assert_eq!(
+            resolve(&team, "missing.quex"),
+            Ok(vec![team.join("missing.quex")])
+        );
+        assert!(resolve(&team, "../shared/*.org").is_err());
+        assert!(resolve(&team, "").is_err());
+
+        let home = std::env::var_os("HOME").unwrap();
+        assert_eq!(
+            resolve(&team, "~/a.quex"),
+            Ok(vec![Path::new(&home).join("a.quex")])
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
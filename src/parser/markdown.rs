@@ -0,0 +1,531 @@
+// Extraction of quex blocks from markdown with a CommonMark parser.
+//
+// A block is any fenced code block, backticks or tildes, indented or nested in
+// a list or a quote, whose info string starts with the block language, e.g.
+// ```` ```quex ```` or `~~~quex title="birthdays"`. A fence mentioned in prose
+// doesn't open a block.
+//
+// Events can also be written inline in the text, mostly in task lists:
+//
+// - [ ] Submit report @due(jan 15 2025)
+// - [ ] Renew passport 📅 2025-01-15
+//
+// `@due` takes any quex date, `📅` takes an ISO date. Done tasks, `- [x]`, are
+// left out.
+//
+// Every line is tagged with the path of headings it is under, e.g.
+// `Projects > Apollo`, which becomes the category of its schedule.
+//
+// A daily note, a file named after its date like `2025-01-15.md`, is an event
+// of its own, as is a note with a `date:` or `due:` key in its YAML front
+// matter. Its description is the first heading of the note.
+
+use pulldown_cmark::{
+    CodeBlockKind, Event, HeadingLevel, MetadataBlockKind, Options, Parser, Tag, TagEnd,
+};
+
+use super::{category, iso_date, walker::QuexLine};
+
+const DUE: &str = "@due(";
+const CALENDAR: &str = "📅";
+/// The front matter keys holding the date of a note.
+const NOTE_DATES: [&str; 2] = ["date", "due"];
+
+fn parser(source: &str) -> Parser<'_> {
+    Parser::new_ext(
+        source,
+        Options::ENABLE_TASKLISTS | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS,
+    )
+}
+
+pub fn is_markdown(extension: &str) -> bool {
+    matches!(extension, "md" | "markdown")
+}
+
+/// The language of a block delimiter if it is a code fence, `quex` for
+/// ```` ```quex ```` or `~~~quex`.
+pub fn fence_language(begin: &str) -> Option<&str> {
+    let fence = begin.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let language = begin.trim_start_matches(fence);
+    let fence_len = begin.len() - language.len();
+
+    match fence_len >= 3 && !language.is_empty() && !language.contains(char::is_whitespace) {
+        true => Some(language),
+        false => None,
+    }
+}
+
+/// The text of a paragraph or a list item, which may hold an inline event.
+#[derive(Default)]
+struct Inline {
+    text: String,
+    /// The text with code spans blanked out, where events are looked for.
+    markers: String,
+    start: Option<usize>,
+}
+
+impl Inline {
+    fn push(&mut self, text: &str, code: bool) {
+        self.text.push_str(text);
+        match code {
+            true => self.markers.push_str(&" ".repeat(text.len())),
+            false => self.markers.push_str(text),
+        }
+    }
+}
+
+/// The lines of every block of the `languages` and of every inline event, with
+/// their line number in `source`.
+pub fn quex_lines(source: &str, languages: &[&str]) -> Vec<QuexLine> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_number = |offset: usize| line_starts.partition_point(|start| *start <= offset);
+
+    let mut lines = vec![];
+    let mut in_code = false;
+    let mut in_block = false;
+    let mut headings: Vec<(HeadingLevel, String)> = vec![];
+    let mut heading: Option<(HeadingLevel, String)> = None;
+
+    let mut inline = Inline::default();
+    // whether each of the enclosing list items is a done task
+    let mut done: Vec<bool> = vec![];
+
+    let flush = |inline: &mut Inline, done: &[bool], headings: &[(HeadingLevel, String)]| {
+        let inline = std::mem::take(inline);
+
+        if done.last() == Some(&true) {
+            return None;
+        }
+
+        let first = line_number(inline.start?);
+        let (text, marker) = inline_event(&inline)?;
+
+        // a paragraph may span lines, the event is on the one of its marker
+        let (number, source) = source
+            .lines()
+            .enumerate()
+            .skip(first - 1)
+            .find(|(_, line)| line.contains(marker))
+            .map(|(i, line)| (i + 1, line))?;
+
+        Some(QuexLine {
+            line_number: number,
+            text,
+            source: Some(source.trim().to_string()),
+            category: category(headings.iter().map(|(_, title)| title.as_str())),
+        })
+    };
+
+    for (event, range) in parser(source).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => heading = Some((level, String::new())),
+            Event::Text(text) | Event::Code(text) if heading.is_some() => {
+                if let Some((_, title)) = heading.as_mut() {
+                    title.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, title)) = heading.take() {
+                    headings.retain(|(parent, _)| *parent < level);
+                    headings.push((level, title.trim().to_string()));
+                }
+            }
+            Event::Start(Tag::MetadataBlock(_)) => in_code = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_code = false,
+            Event::Start(Tag::CodeBlock(kind)) => {
+                in_code = true;
+                in_block = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .is_some_and(|lang| languages.contains(&lang)),
+                    CodeBlockKind::Indented => false,
+                };
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                in_code = false;
+                in_block = false;
+            }
+            Event::Text(text) if in_block => {
+                let first = line_number(range.start);
+                let category = category(headings.iter().map(|(_, title)| title.as_str()));
+
+                lines.extend(text.lines().enumerate().map(|(i, line)| QuexLine {
+                    line_number: first + i,
+                    text: line.to_string(),
+                    source: None,
+                    category: category.clone(),
+                }));
+            }
+            Event::Text(text) if !in_code => {
+                inline.start.get_or_insert(range.start);
+                inline.push(&text, false);
+            }
+            Event::Code(text) => {
+                inline.start.get_or_insert(range.start);
+                inline.push(&format!("`{text}`"), true);
+            }
+            Event::SoftBreak | Event::HardBreak => inline.push(" ", false),
+            Event::TaskListMarker(checked) => {
+                if let Some(done) = done.last_mut() {
+                    *done = checked;
+                }
+            }
+            // a nested list ends the text of its parent item
+            Event::Start(Tag::Item) => {
+                lines.extend(flush(&mut inline, &done, &headings));
+                done.push(false);
+            }
+            Event::End(TagEnd::Item) => {
+                lines.extend(flush(&mut inline, &done, &headings));
+                done.pop();
+            }
+            Event::End(TagEnd::Paragraph) => lines.extend(flush(&mut inline, &done, &headings)),
+            _ => {}
+        }
+    }
+
+    lines.sort_by_key(|line| line.line_number);
+    lines
+}
+
+/// The events of a note, from its front matter or, when it has no dates
+/// there, from its file name `stem`. They are described by the `title` field
+/// of the front matter, by the first heading or by the file name.
+pub fn note_lines(source: &str, stem: &str, title: Option<&str>) -> Vec<QuexLine> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_number = |offset: usize| line_starts.partition_point(|start| *start <= offset);
+
+    // (line number, key, value) of every front matter field
+    let mut fields = vec![];
+    let mut in_metadata = false;
+    let mut in_heading = false;
+    let mut heading: Option<String> = None;
+
+    for (event, range) in parser(source).into_offset_iter() {
+        match event {
+            Event::Start(Tag::MetadataBlock(MetadataBlockKind::YamlStyle)) => in_metadata = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_metadata = false,
+            Event::Text(text) if in_metadata => {
+                let first = line_number(range.start);
+
+                fields.extend(text.lines().enumerate().filter_map(|(i, line)| {
+                    let (key, value) = line.split_once(':')?;
+                    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+
+                    Some((first + i, key.trim().to_string(), value.to_string()))
+                }));
+            }
+            Event::Start(Tag::Heading { .. }) if heading.is_none() => {
+                in_heading = true;
+                heading = Some(String::new());
+            }
+            Event::Text(text) | Event::Code(text) if in_heading => {
+                if let Some(heading) = heading.as_mut() {
+                    heading.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => in_heading = false,
+            _ => {}
+        }
+    }
+
+    let description = title
+        .and_then(|title| fields.iter().find(|(_, key, _)| key == title))
+        .map(|(_, _, value)| value.clone())
+        .or(heading.map(|heading| heading.trim().to_string()))
+        .filter(|description| !description.is_empty())
+        .unwrap_or(stem.to_string());
+
+    let mut lines: Vec<_> = fields
+        .iter()
+        .filter(|(_, key, value)| NOTE_DATES.contains(&key.as_str()) && !value.is_empty())
+        .map(|(line_number, _, value)| QuexLine {
+            line_number: *line_number,
+            text: format!("{}, {description}", note_date(value)),
+            source: source
+                .lines()
+                .nth(line_number - 1)
+                .map(|line| line.trim().to_string()),
+            category: None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        lines.extend(iso_date(stem).map(|date| QuexLine {
+            line_number: 1,
+            text: format!("{date}, {description}"),
+            // the date comes from the file name
+            source: Some(stem.to_string()),
+            category: None,
+        }));
+    }
+
+    lines
+}
+
+/// A front matter date as a quex date, ISO dates like `2025-01-15` or
+/// `2025-01-15T09:00` are converted, anything else is taken as is.
+fn note_date(value: &str) -> String {
+    value
+        .get(..10)
+        .and_then(iso_date)
+        .unwrap_or(value.to_string())
+}
+
+/// The quex line of an inline event and the marker it was found by, both
+/// `Submit report @due(jan 15 2025)` and `Submit report 📅 2025-01-15` give
+/// `jan 15 2025, Submit report`.
+fn inline_event(inline: &Inline) -> Option<(String, &'static str)> {
+    let (text, markers) = (&inline.text, &inline.markers);
+
+    let due = markers.find(DUE).and_then(|start| {
+        let len = markers[start..].find(')')?;
+        let date = text[start + DUE.len()..start + len].trim().to_string();
+
+        Some((date, start, start + len + 1, DUE))
+    });
+
+    // an unclosed `@due(` leaves the line to its 📅, if any
+    let (date, start, end, marker) = match due {
+        Some(due) => due,
+        None => {
+            let start = markers.find(CALENDAR)?;
+            let after = text[start + CALENDAR.len()..].trim_start();
+            let len = after.find(char::is_whitespace).unwrap_or(after.len());
+
+            let end = text.len() - after.len() + len;
+
+            (iso_date(&after[..len])?, start, end, CALENDAR)
+        }
+    };
+
+    let description = format!("{} {}", &text[..start], &text[end..]);
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Some((format!("{date}, {description}"), marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fence_language, note_lines, quex_lines};
+    use crate::parser::walker::QuexLine;
+
+    #[test]
+    fn fences() {
+        assert_eq!(fence_language("```quex"), Some("quex"));
+        assert_eq!(fence_language("~~~~quex"), Some("quex"));
+        assert_eq!(fence_language("``quex"), None);
+        assert_eq!(fence_language("#+begin_src quex"), None);
+    }
+
+    #[test]
+    fn blocks() {
+        let source = "# Notes
+
+Blocks are opened with ```quex in prose too.
+
+```quex title=\"first\"
+jan 1 2025, one
+```
+
+~~~quex
+jan 2 2025, two
+~~~
+
+- item
+
+  ```quex
+  jan 3 2025, three
+  ```
+
+> ```quex
+> jan 4 2025, four
+> ```
+
+```rust
+jan 5 2025, not quex
+```
+";
+
+        let lines: Vec<_> = quex_lines(source, &["quex"])
+            .into_iter()
+            .map(|line| (line.line_number, line.text))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                (6, "jan 1 2025, one".to_string()),
+                (10, "jan 2 2025, two".to_string()),
+                (16, "jan 3 2025, three".to_string()),
+                (20, "jan 4 2025, four".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn categories() {
+        let source = "# Projects
+
+## Apollo `v2`
+
+```quex
+jan 1 2025, launch
+```
+
+# Home
+
+```quex
+jan 2 2025, groceries
+```
+";
+
+        assert_eq!(
+            quex_lines(source, &["quex"]),
+            [
+                QuexLine {
+                    line_number: 6,
+                    text: "jan 1 2025, launch".to_string(),
+                    source: None,
+                    category: Some("Projects > Apollo v2".to_string()),
+                },
+                QuexLine {
+                    line_number: 12,
+                    text: "jan 2 2025, groceries".to_string(),
+                    source: None,
+                    category: Some("Home".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_events() {
+        let source = "# Work
+
+- [ ] Submit report @due(jan 15 2025)
+- [x] Book flights @due(jan 10 2025)
+- [ ] Renew *passport* 📅 2025-02-01
+  - [ ] Photos @due(jan 20 2025)
+
+Call the bank
+@due(d=5) about the loan.
+
+`@due(jan 1 2025)` in code doesn't count, @due without a date neither.
+
+Pay rent 📅 tomorrow isn't an event.
+
+Cancel gym @due(jan 5 2025 📅 2025-01-06
+
+```
+Nor in a block @due(jan 2 2025)
+```
+";
+
+        let lines = quex_lines(source, &["quex"]);
+        let sources: Vec<_> = lines.iter().map(|line| line.source.as_deref()).collect();
+        assert_eq!(
+            sources,
+            [
+                Some("- [ ] Submit report @due(jan 15 2025)"),
+                Some("- [ ] Renew *passport* 📅 2025-02-01"),
+                Some("- [ ] Photos @due(jan 20 2025)"),
+                Some("@due(d=5) about the loan."),
+                Some("Cancel gym @due(jan 5 2025 📅 2025-01-06"),
+            ]
+        );
+
+        let lines: Vec<_> = lines
+            .into_iter()
+            .map(|line| (line.line_number, line.text, line.category))
+            .collect();
+
+        let work = Some("Work".to_string());
+        assert_eq!(
+            lines,
+            [
+                (3, "jan 15 2025, Submit report".to_string(), work.clone()),
+                (5, "feb 1 2025, Renew passport".to_string(), work.clone()),
+                (6, "jan 20 2025, Photos".to_string(), work.clone()),
+                (
+                    9,
+                    "d=5, Call the bank about the loan.".to_string(),
+                    work.clone()
+                ),
+                (
+                    15,
+                    "jan 6 2025, Cancel gym @due(jan 5 2025".to_string(),
+                    work.clone()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn notes() {
+        let text = |lines: Vec<QuexLine>| -> Vec<_> {
+            lines
+                .into_iter()
+                .map(|line| (line.line_number, line.text, line.source.unwrap()))
+                .collect()
+        };
+
+        let daily = "# Standup\n\nNothing new.\n";
+        assert_eq!(
+            text(note_lines(daily, "2025-01-15", None)),
+            [(
+                1,
+                "jan 15 2025, Standup".to_string(),
+                "2025-01-15".to_string()
+            )]
+        );
+        assert!(note_lines(daily, "standup", None).is_empty());
+
+        let front_matter = "---
+title: Quarterly review
+date: 2025-03-01T09:00
+due: \"apr 1 2025\"
+---
+
+# Review
+";
+        assert_eq!(
+            text(note_lines(front_matter, "2025-01-15", Some("title"))),
+            [
+                (
+                    3,
+                    "mar 1 2025, Quarterly review".to_string(),
+                    "date: 2025-03-01T09:00".to_string()
+                ),
+                (
+                    4,
+                    "apr 1 2025, Quarterly review".to_string(),
+                    "due: \"apr 1 2025\"".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            text(note_lines(front_matter, "review", None)),
+            [
+                (
+                    3,
+                    "mar 1 2025, Review".to_string(),
+                    "date: 2025-03-01T09:00".to_string()
+                ),
+                (
+                    4,
+                    "apr 1 2025, Review".to_string(),
+                    "due: \"apr 1 2025\"".to_string()
+                ),
+            ]
+        );
+
+        // the front matter isn't taken for a heading
+        assert!(quex_lines(front_matter, &["quex"]).is_empty());
+    }
+}
@@ -1,14 +1,29 @@
+pub mod block;
+pub mod cache;
 pub mod date_window;
+mod ignore;
+mod include;
+mod markdown;
+mod org;
 pub mod schedule;
 pub mod walker;
 
 #[cfg(feature = "eth")]
 use crate::calender::eth::parse_eth_quex;
 
+use std::path::{Path, PathBuf};
+
+use pest::Parser as _;
+use pest_derive::Parser;
+
 use crate::calender::gre::parse_gre_quex;
 use crate::calender::LineError;
 use crate::Schedule;
 
+#[derive(Parser)]
+#[grammar = "parser/grammar/base.pest"]
+struct BaseParser;
+
 // The length I go to mimic parser combinators ;)
 macro_rules! run_parsers {
     () => {};
@@ -32,14 +47,75 @@ macro_rules! run_parsers {
 }
 
 pub fn parse_line(line: &str) -> Result<Schedule, LineError> {
+    parse_event(line).map(|mut schedule| {
+        schedule.raw = line.to_string();
+        schedule
+    })
+}
+
+fn parse_event(line: &str) -> Result<Schedule, LineError> {
     #[cfg(feature = "eth")]
     run_parsers!(line, parse_gre_quex, parse_eth_quex);
     #[cfg(not(feature = "eth"))]
     run_parsers!(line, parse_gre_quex);
 }
 
+/// Whether a line is blank or commented out, and so skipped rather than parsed.
+pub fn is_ignored(line: &str) -> bool {
+    BaseParser::parse(Rule::ignored_line, line).is_ok()
+}
+
+/// The path with a leading `~/` replaced by the home directory, as the shell
+/// does for the paths given on the command line.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The titles of the headings a line is under joined into its category,
+/// `Projects > Apollo`, `None` outside of any heading.
+fn category<'a>(titles: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let titles: Vec<_> = titles.into_iter().collect();
+
+    match titles.is_empty() {
+        true => None,
+        false => Some(titles.join(" > ")),
+    }
+}
+
+/// `2025-01-15` as a quex date, `jan 15 2025`.
+fn iso_date(date: &str) -> Option<String> {
+    let mut parts = date.splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month = time::Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+
+    let month = month.to_string()[..3].to_lowercase();
+    Some(format!("{month} {day} {year}"))
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{is_ignored, parse_line};
+
+    #[test]
+    fn comments() {
+        assert!(is_ignored(""));
+        assert!(is_ignored(" \t "));
+        assert!(is_ignored("# work"));
+        assert!(is_ignored("  // work"));
+        assert!(!is_ignored("jan 1 2025, new year"));
+
+        let description = |line| parse_line(line).unwrap().description;
+        assert_eq!(description("jan 1 2025, new year // party"), "new year");
+        assert_eq!(description("jan 1 2025, new year\t// party"), "new year");
+        assert_eq!(description("jan 1 2025, see http://a.b"), "see http://a.b");
+        assert_eq!(description("jan 1 2025, a \\// b"), "a // b");
+        assert_eq!(description("d=5, rent #home"), "rent #home");
+    }
+
     //     use super::Calender;
     //     use super::Schedule;
     //     use time::Date;
@@ -0,0 +1,279 @@
+// Extraction of quex blocks and timestamps from org files.
+//
+// A block is a source block of a block language, matched regardless of
+// case, `#+BEGIN_SRC quex` or `#+begin_src quex :tangle no`.
+//
+// Active timestamps under a heading are events described by the heading,
+// whether they are on a planning line or in the text:
+//
+// * TODO Submit report :work:
+//   DEADLINE: <2025-01-15 Wed>
+//
+// A `+1y` or `+1m` repeater makes the event recur yearly or monthly. Inactive
+// timestamps, `[2025-01-15 Wed]`, and the timestamps of DONE headings are
+// left out. Every line is tagged with the path of headings it is under.
+
+use super::{category, iso_date, walker::QuexLine};
+
+const BEGIN_SRC: &str = "#+begin_src";
+const END_SRC: &str = "#+end_src";
+const BEGIN: &str = "#+begin_";
+
+pub fn is_org(extension: &str) -> bool {
+    extension == "org"
+}
+
+/// The language of a block delimiter if it opens a source block, `quex` for
+/// `#+begin_src quex`.
+pub fn src_language(begin: &str) -> Option<&str> {
+    let keyword = begin.get(..BEGIN_SRC.len())?;
+
+    match keyword.eq_ignore_ascii_case(BEGIN_SRC) {
+        true => begin[BEGIN_SRC.len()..].split_whitespace().next(),
+        false => None,
+    }
+}
+
+/// A heading, its level and its title without the keyword, priority and tags.
+struct Heading {
+    level: usize,
+    title: String,
+    done: bool,
+}
+
+impl Heading {
+    fn parse(line: &str) -> Option<Self> {
+        let level = line.len() - line.trim_start_matches('*').len();
+        if level == 0 || !line[level..].starts_with(' ') {
+            return None;
+        }
+
+        let mut words: Vec<&str> = line[level..].split_whitespace().collect();
+        let done = words.first() == Some(&"DONE");
+
+        if matches!(words.first(), Some(&"TODO") | Some(&"DONE")) {
+            words.remove(0);
+        }
+        if words.first().is_some_and(|word| is_priority(word)) {
+            words.remove(0);
+        }
+        if words.last().is_some_and(|word| is_tags(word)) {
+            words.pop();
+        }
+
+        Some(Self {
+            level,
+            title: strip_timestamps(&words.join(" ")),
+            done,
+        })
+    }
+}
+
+/// The lines of every block of the `languages` and of every active timestamp,
+/// with their line number in `source`.
+pub fn quex_lines(source: &str, languages: &[&str]) -> Vec<QuexLine> {
+    let mut lines = vec![];
+    let mut in_block = false;
+    let mut in_other_block = false;
+    let mut headings: Vec<Heading> = vec![];
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        let lowercase = trimmed.to_lowercase();
+
+        if in_block || in_other_block {
+            if lowercase.starts_with(END_SRC) || (in_other_block && lowercase.starts_with("#+end_"))
+            {
+                in_block = false;
+                in_other_block = false;
+            } else if in_block {
+                lines.push(QuexLine {
+                    line_number,
+                    text: line.to_string(),
+                    source: None,
+                    category: category(headings.iter().map(|heading| heading.title.as_str())),
+                });
+            }
+            continue;
+        }
+
+        let language = src_language(trimmed);
+        if languages
+            .iter()
+            .any(|lang| language.is_some_and(|language| language.eq_ignore_ascii_case(lang)))
+        {
+            in_block = true;
+            continue;
+        }
+        if lowercase.starts_with(BEGIN) {
+            in_other_block = true;
+            continue;
+        }
+
+        let timestamps = match Heading::parse(line) {
+            Some(heading) => {
+                headings.retain(|parent| parent.level < heading.level);
+                headings.push(heading);
+                timestamps(line)
+            }
+            None => timestamps(trimmed),
+        };
+
+        let Some(heading) = headings.last().filter(|heading| !heading.done) else {
+            continue;
+        };
+
+        // the parent headings, the heading itself is the description
+        let category = category(
+            headings[..headings.len() - 1]
+                .iter()
+                .map(|heading| heading.title.as_str()),
+        );
+
+        lines.extend(timestamps.into_iter().map(|date| QuexLine {
+            line_number,
+            text: format!("{date}, {}", heading.title),
+            source: Some(trimmed.to_string()),
+            category: category.clone(),
+        }));
+    }
+
+    lines
+}
+
+/// The quex dates of the active timestamps of a line. The end of a range,
+/// `<2025-01-15 Wed>--<2025-01-17 Fri>`, is left out.
+fn timestamps(line: &str) -> Vec<String> {
+    let mut dates = vec![];
+    let mut rest = line;
+
+    while let Some(start) = rest.find('<') {
+        let is_range_end = rest[..start].ends_with("--");
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+
+        let timestamp = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        if is_range_end {
+            continue;
+        }
+        if let Some(date) = timestamp_date(timestamp) {
+            dates.push(date);
+        }
+    }
+
+    dates
+}
+
+/// `2025-01-15 Wed +1y` as a quex date, `jan 15 2025*`.
+fn timestamp_date(timestamp: &str) -> Option<String> {
+    let mut parts = timestamp.split_whitespace();
+    let iso = parts.next()?;
+    let date = iso_date(iso)?;
+
+    let repeater = parts.find(|part| part.starts_with(['+', '.']));
+    let date = match repeater.map(|repeater| repeater.trim_start_matches(['+', '.'])) {
+        Some("1y") => format!("{date}*"),
+        Some("1m") => format!("d={}", iso.rsplit('-').next()?.trim_start_matches('0')),
+        _ => date,
+    };
+
+    Some(date)
+}
+
+/// The text of a heading with its timestamps taken out.
+fn strip_timestamps(title: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = title;
+
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+
+        match timestamp_date(&rest[start + 1..start + len]) {
+            Some(_) => stripped.push_str(rest[..start].trim_end_matches("--")),
+            None => stripped.push_str(&rest[..start + len + 1]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    stripped.push_str(rest);
+
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `[#A]`
+fn is_priority(word: &str) -> bool {
+    word.len() == 4 && word.starts_with("[#") && word.ends_with(']')
+}
+
+/// `:work:urgent:`
+fn is_tags(word: &str) -> bool {
+    word.len() > 2 && word.starts_with(':') && word.ends_with(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quex_lines, src_language};
+
+    #[test]
+    fn src_blocks() {
+        assert_eq!(src_language("#+begin_src quex"), Some("quex"));
+        assert_eq!(src_language("#+BEGIN_SRC quex :tangle no"), Some("quex"));
+        assert_eq!(src_language("```quex"), None);
+    }
+
+    #[test]
+    fn timestamps() {
+        let source = "#+title: Notes
+<2025-01-01 Wed> before any heading
+
+* Work
+** TODO [#A] Submit report :urgent:
+   DEADLINE: <2025-01-15 Wed> SCHEDULED: <2025-01-13 Mon>
+** DONE Book flights
+   SCHEDULED: <2025-01-10 Fri>
+** Offsite <2025-02-03 Mon>--<2025-02-05 Wed>
+   Noted on [2025-01-02 Thu].
+* Home
+  Rent is due <2025-01-01 Wed +1m>, and
+  #+BEGIN_SRC quex
+  jan 6 2025*, anniversary
+  #+END_SRC
+  #+begin_example
+  <2025-03-01 Sat>
+  #+end_example
+** Birthday <1990-05-04 Fri +1y>
+";
+
+        let lines = quex_lines(source, &["quex"]);
+        // the timestamps are reported with the line they're on
+        assert_eq!(
+            lines[0].source.as_deref(),
+            Some("DEADLINE: <2025-01-15 Wed> SCHEDULED: <2025-01-13 Mon>")
+        );
+        assert_eq!(lines[4].source, None);
+
+        let lines: Vec<_> = lines
+            .into_iter()
+            .map(|line| (line.line_number, line.text, line.category))
+            .collect();
+
+        let work = Some("Work".to_string());
+        let home = Some("Home".to_string());
+        assert_eq!(
+            lines,
+            [
+                (6, "jan 15 2025, Submit report".to_string(), work.clone()),
+                (6, "jan 13 2025, Submit report".to_string(), work.clone()),
+                (9, "feb 3 2025, Offsite".to_string(), work.clone()),
+                (12, "d=1, Home".to_string(), None),
+                (14, "  jan 6 2025*, anniversary".to_string(), home.clone()),
+                (19, "may 4 1990*, Birthday".to_string(), home.clone()),
+            ]
+        );
+    }
+}
@@ -1,14 +1,31 @@
-use serde_derive::Serialize;
+use std::path::PathBuf;
 
-use crate::calender::{DateInfo, Event};
+use crate::calender::{CalendarKind, DateInfo, Event, Recurrence};
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq)]
 pub struct Schedule {
     pub description: String,
-    #[serde(skip_serializing)]
     pub julian_day_number: i32,
     pub diff: i32,
     pub date: String,
+    pub source_path: Option<PathBuf>,
+    pub line_number: Option<usize>,
+    pub calendar: CalendarKind,
+    pub recurrence: Recurrence,
+    /// The line of the file the schedule was read from, e.g. the list item
+    /// of an inline event.
+    pub raw: String,
+    /// The markdown or org headings the schedule was found under, e.g. `Projects > Apollo`.
+    pub category: Option<String>,
+}
+
+impl Schedule {
+    /// The date in ISO 8601 (`YYYY-MM-DD`), always in the Gregorian calendar.
+    pub fn iso_date(&self) -> String {
+        time::Date::from_julian_day(self.julian_day_number)
+            .map(|date| date.to_string())
+            .unwrap_or_default()
+    }
 }
 
 impl<T: DateInfo> From<Event<T>> for Schedule {
@@ -21,6 +38,12 @@ impl<T: DateInfo> From<Event<T>> for Schedule {
             julian_day_number: date,
             diff: date - today.to_julian_day(),
             date: event.date.pretty_print(),
+            source_path: None,
+            line_number: None,
+            calendar: event.date.calendar(),
+            recurrence: event.recurrence,
+            raw: String::new(),
+            category: None,
         }
     }
 }
@@ -1,138 +1,729 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::OsStr,
-    fs::File,
-    io::{BufRead, BufReader},
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
 };
 
+use rayon::prelude::*;
+
 use crate::{
+    calender::LineError,
     error::{io, Error, ValueError},
-    parser::{self, Schedule},
+    parser::{
+        self,
+        block::{self, Block, FileFormat},
+        cache::{self, Cache, CachedFile, Entry},
+        ignore::{self, Rule},
+        include, markdown, org, Schedule,
+    },
 };
 
 pub type Schedules = Vec<Schedule>;
 pub type QErrors = Vec<Error>;
 
-pub fn walk_dir(
+/// The calendar root read from stdin rather than from a path.
+pub const STDIN: &str = "-";
+/// What the errors of the calendar read from stdin are reported under.
+const STDIN_NAME: &str = "<stdin>";
+
+/// How the files of a calendar are read.
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// The formats read, keyed by file extension.
+    pub file_formats: HashMap<String, FileFormat>,
+    /// The front matter field daily notes take their description from, the
+    /// first heading is used when it's missing.
+    pub note_title: Option<String>,
+    /// Globs of the files to read, every file is read when empty.
+    pub include: Vec<String>,
+    /// Globs of the files and directories to skip.
+    pub exclude: Vec<String>,
+    /// Walk hidden directories too.
+    pub hidden: bool,
+    /// How many threads read the files, one per core when `None`.
+    pub threads: Option<usize>,
+    /// The file the parsed files are cached in, nothing is cached when `None`.
+    pub cache: Option<PathBuf>,
+    /// The extension the calendar read from stdin is parsed as, e.g. `md`.
+    pub stdin_format: String,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            file_formats: FileFormat::defaults(),
+            note_title: None,
+            include: vec![],
+            exclude: vec![],
+            hidden: false,
+            threads: None,
+            cache: None,
+            stdin_format: "quex".to_string(),
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Checks the include and exclude globs.
+    pub fn validate(&self) -> Result<(), glob::PatternError> {
+        self.include
+            .iter()
+            .chain(&self.exclude)
+            .try_for_each(|glob| Rule::new(Path::new(""), glob).map(|_| ()))
+    }
+}
+
+/// The state of a walk through a calendar root, shared by the threads walking
+/// its directories.
+struct Walk<'a> {
+    options: &'a WalkOptions,
+    include: Vec<Rule>,
+}
+
+/// The files found directly in a directory of a calendar.
+struct Listing {
+    /// The canonical path of the directory, `None` for a file given as a root.
+    canonical: Option<PathBuf>,
     path: PathBuf,
-    file_formats: &HashMap<String, String>,
-) -> Result<(Schedules, QErrors), io::FileError> {
-    if path.is_dir() {
-        let mut schedules: Schedules = vec![];
-        let mut errors = vec![];
-
-        let entries = match path.read_dir() {
-            Ok(entries) => entries,
-            Err(e) => return Err(io::FileError::new(path, e)),
+    files: Vec<PathBuf>,
+}
+
+impl Walk<'_> {
+    /// Whether an entry is left out, `ignore` holds the exclude globs and the
+    /// `.quexignore` rules of the directories above it.
+    fn skips(&self, path: &Path, is_dir: bool, ignore: &[Rule]) -> bool {
+        if is_dir && !self.options.hidden && ignore::is_hidden(path) {
+            return true;
+        }
+        if ignore.iter().any(|rule| rule.matches(path, is_dir)) {
+            return true;
+        }
+
+        !is_dir
+            && !self.include.is_empty()
+            && !self.include.iter().any(|rule| rule.matches(path, is_dir))
+    }
+}
+
+/// A line holding a quex schedule, and where it was found in its file.
+#[derive(Debug, PartialEq)]
+pub struct QuexLine {
+    pub line_number: usize,
+    pub text: String,
+    /// The line of the file the quex line was built from, e.g. the list item
+    /// of an inline event, `None` when the text is the line itself.
+    pub source: Option<String>,
+    /// Where the line sits in the document, e.g. the markdown or org headings above it.
+    pub category: Option<String>,
+}
+
+fn parse_into(path: &Path, line: QuexLine, schedules: &mut Schedules, errors: &mut Error) {
+    let QuexLine {
+        line_number,
+        text,
+        source,
+        category,
+    } = line;
+
+    match parser::parse_line(text.trim()) {
+        Ok(mut event) => {
+            event.source_path = Some(path.to_path_buf());
+            event.line_number = Some(line_number);
+            event.category = category;
+            if let Some(source) = source {
+                event.raw = source;
+            }
+            schedules.push(event)
+        }
+        Err(e) => errors.push(ValueError::new(e, line_number, source.unwrap_or(text))),
+    }
+}
+
+fn into_results(schedules: Schedules, errors: Error) -> (Schedules, QErrors) {
+    match errors.is_empty() {
+        true => (schedules, vec![]),
+        false => (schedules, vec![errors]),
+    }
+}
+
+/// The quex lines of a file of a configured format.
+fn format_lines(
+    path: &Path,
+    extension: &str,
+    format: &FileFormat,
+    source: &str,
+    options: &WalkOptions,
+) -> Vec<QuexLine> {
+    let mut lines = vec![];
+    let mut blocks: Vec<&Block> = vec![];
+    let mut languages: Vec<&str> = vec![];
+
+    // markdown fences are found by a CommonMark parser and org source blocks
+    // are matched regardless of case, rather than by looking for the
+    // delimiters
+    let language = match extension {
+        ext if markdown::is_markdown(ext) => markdown::fence_language,
+        ext if org::is_org(ext) => org::src_language,
+        _ => |_: &str| None,
+    };
+
+    for block in &format.blocks {
+        match language(&block.start).filter(|_| !block.regex) {
+            Some(lang) => languages.push(lang),
+            None => blocks.push(block),
+        }
+    }
+
+    if markdown::is_markdown(extension) {
+        let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or("");
+
+        lines.extend(markdown::quex_lines(source, &languages));
+        lines.extend(markdown::note_lines(
+            source,
+            stem,
+            options.note_title.as_deref(),
+        ));
+    } else if org::is_org(extension) {
+        // org files are read for their timestamps too
+        lines.extend(org::quex_lines(source, &languages));
+    }
+
+    lines.extend(block::quex_lines(
+        source,
+        &blocks,
+        format.comment.as_deref(),
+    ));
+    lines.sort_by_key(|line| line.line_number);
+    lines
+}
+
+/// Reads the schedules of every root. The directories are walked and the
+/// files read in parallel, the results are sorted by path and line so they
+/// don't depend on the order the threads finish in.
+///
+/// A file reached twice, through a symlink loop or overlapping roots, or
+/// given as a root inside a directory that's walked too, is only read once. A file or directory that can't be read is reported among
+/// the errors, the rest of the calendar is still read.
+pub fn walk_dirs(roots: &[PathBuf], options: &WalkOptions) -> (Schedules, QErrors) {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.threads.unwrap_or(0))
+        .build();
+
+    match pool {
+        Ok(pool) => pool.install(|| walk_roots(roots, options)),
+        // without a pool of its own the walk runs on the global one
+        Err(_) => walk_roots(roots, options),
+    }
+}
+
+fn walk_roots(roots: &[PathBuf], options: &WalkOptions) -> (Schedules, QErrors) {
+    let mut listings = vec![];
+    let mut schedules = vec![];
+    let mut errors = vec![];
+
+    for root in roots {
+        // stdin is read once the walk knows which files it reads itself
+        if root.as_os_str() == STDIN {
+            continue;
+        }
+
+        if let Err(e) = fs::metadata(root) {
+            errors.push(io_error(root.clone(), e));
+            continue;
+        }
+
+        let rules = |globs: &[String]| -> Vec<Rule> {
+            globs
+                .iter()
+                .filter_map(|glob| Rule::new(root, glob).ok())
+                .collect()
         };
 
-        // generic io failure
-        for entry in entries {
-            // generic io failure
-            let ent = match entry {
-                Ok(e) => e.path(),
-                Err(e) => return Err(io::FileError::new(path, e)),
+        let walk = Walk {
+            options,
+            include: rules(&options.include),
+        };
+
+        let (dirs, errs) = walk_dir(root.clone(), &walk, &rules(&options.exclude), &[]);
+        listings.extend(dirs);
+        errors.extend(errs);
+    }
+
+    // a file reached twice, through a symlink or overlapping roots, is read
+    // from its smallest path, whichever thread got to it first; `walked` holds
+    // the canonical paths of the files read, an include of one is left out
+    listings.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut walked = HashSet::new();
+    let mut files = vec![];
+
+    for dir in listings {
+        for file in dir.files {
+            let canonical = match &dir.canonical {
+                Some(canonical) => file.file_name().map(|name| canonical.join(name)),
+                None => fs::canonicalize(&file).ok(),
             };
 
-            let (schs, errs) = walk_dir(ent, file_formats)?;
+            if canonical.is_none_or(|canonical| walked.insert(canonical)) {
+                files.push(file);
+            }
+        }
+    }
+
+    if roots.iter().any(|root| root.as_os_str() == STDIN) {
+        let (schs, errs) = read_stdin(options, &walked);
+        schedules.extend(schs);
+        errors.extend(errs);
+    }
+
+    let mut cache = options
+        .cache
+        .as_deref()
+        .map(|path| Cache::load(path, options));
+
+    let read: Vec<_> = files
+        .par_iter()
+        .map(|path| read_file(path, options, &walked, cache.as_ref()))
+        .collect();
 
-            schedules.extend(schs);
-            errors.extend(errs);
+    let mut changed = false;
+
+    for (schs, errs, cached) in read {
+        schedules.extend(schs);
+        errors.extend(errs);
+
+        if let (Some(cache), Some((key, file))) = (cache.as_mut(), cached) {
+            cache.insert(key, file);
+            changed = true;
         }
-        Ok((schedules, errors))
-    } else {
-        let file_extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+    }
 
-        if let Some(block) = file_formats.get(file_extension) {
-            let mut bparts = block.split(',');
-            let begin = bparts.next().unwrap().trim();
-            let end = bparts.next().unwrap().trim();
+    // the cache is only an optimization, failing to write it isn't an error
+    if let (Some(cache), Some(path), true) = (cache, &options.cache, changed) {
+        let _ = cache.save(path);
+    }
 
+    schedules.sort_by(|a, b| (&a.source_path, a.line_number).cmp(&(&b.source_path, b.line_number)));
+    errors.sort_by(|a, b| a.path().cmp(b.path()));
 
-        let file = match File::open(&path) {
-            Ok(file) => file,
-            Err(e) => return Err(io::FileError::new(path, e)),
+    (schedules, errors)
+}
+
+fn io_error(path: PathBuf, error: std::io::Error) -> Error {
+    io::FileError::new(path, error).into()
+}
+
+/// The files to read under `path`, listed per directory. `ignore` holds the
+/// exclude globs and the `.quexignore` rules of the directories above it, and
+/// `ancestors` their canonical paths, to stop at a symlink loop.
+fn walk_dir(
+    path: PathBuf,
+    walk: &Walk,
+    ignore: &[Rule],
+    ancestors: &[PathBuf],
+) -> (Vec<Listing>, QErrors) {
+    if !path.is_dir() {
+        let listing = Listing {
+            canonical: None,
+            files: vec![path.clone()],
+            path,
         };
+        return (vec![listing], vec![]);
+    }
 
+    let canonical = match fs::canonicalize(&path) {
+        Ok(canonical) => canonical,
+        Err(e) => return (vec![], vec![io_error(path, e)]),
+    };
+    if ancestors.contains(&canonical) {
+        return (vec![], vec![]);
+    }
 
-            let reader = BufReader::new(file);
-            let mut line_iter = reader.lines().enumerate();
-            let mut schedules: Schedules = vec![];
-            let mut errors = Error::new(path.clone());
+    let entries = match path.read_dir() {
+        Ok(entries) => entries,
+        Err(e) => return (vec![], vec![io_error(path, e)]),
+    };
 
-            loop {
-                let Some((_, line)) = line_iter.next() else {
-                    // NOTE: recursion is hard
-                    let rec_is_hard = if errors.is_empty() {
-                        Vec::with_capacity(0)
-                    } else {
-                        vec![errors]
-                    };
+    let mut rules: Vec<Rule> = ignore.to_vec();
+    rules.extend(ignore::ignore_file(&path));
 
-                    break Ok((schedules, rec_is_hard));
-                };
-
-                let line = match line {
-                    Ok(line) => line,
-                    Err(e) => return Err(io::FileError::new(path, e)),
-                }; // file read error ? maybe generic
-
-                if line.trim().contains(begin) {
-                    for (line_number, line) in line_iter.by_ref() {
-                        let line = match line {
-                            Ok(line) => line,
-                            // TODO: Handle EOF
-                            Err(e) => return Err(io::FileError::new(path, e)),
-                        };
-
-                        if line.trim().contains(end) {
-                            break;
-                        }
-
-                        match parser::parse_line(line.as_str().trim()) {
-                            Ok(event) => schedules.push(event),
-                            Err(e) => errors.push(ValueError::new(e, line_number + 1, line)),
-                        };
-                    }
-                }
-            }
-        } else if file_extension == "quex" {
-            let file = match File::open(&path) {
-                Ok(file) => file,
-                Err(e) => return Err(io::FileError::new(path, e)),
-            };
+    let mut errors = vec![];
+    let mut children = vec![];
+
+    for entry in entries {
+        match entry {
+            Ok(entry) => children.push(entry.path()),
+            Err(e) => errors.push(io_error(path.clone(), e)),
+        }
+    }
+
+    let (dirs, mut files): (Vec<_>, Vec<_>) = children
+        .into_iter()
+        .map(|child| (child.is_dir(), child))
+        .filter(|(is_dir, child)| !walk.skips(child, *is_dir, &rules))
+        .partition(|(is_dir, _)| *is_dir);
+    files.sort();
+
+    let ancestors = [ancestors, std::slice::from_ref(&canonical)].concat();
+    let (listings, errs): (Vec<_>, Vec<_>) = dirs
+        .into_par_iter()
+        .map(|(_, dir)| walk_dir(dir, walk, &rules, &ancestors))
+        .unzip();
+
+    let mut listings: Vec<Listing> = listings.into_iter().flatten().collect();
+    listings.push(Listing {
+        canonical: Some(canonical),
+        path,
+        files: files.into_iter().map(|(_, file)| file).collect(),
+    });
+
+    errors.extend(errs.into_iter().flatten());
+    (listings, errors)
+}
+
+/// The schedules of a file that was cached, or read again with a cache.
+type ReadFile = (Schedules, QErrors, Option<(PathBuf, CachedFile)>);
 
-            let reader = BufReader::new(file);
-            let mut schedules = vec![];
-            let mut errors = Error::new(path.clone());
+/// The schedules and errors of a file, nothing for a file of no known format.
+/// With a cache, the file is only parsed when it changed, and then its new
+/// cache entry is returned too.
+fn read_file(
+    path: &Path,
+    options: &WalkOptions,
+    walked: &HashSet<PathBuf>,
+    cache: Option<&Cache>,
+) -> ReadFile {
+    let file_extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+
+    let format = options.file_formats.get(file_extension);
+    if format.is_none() && file_extension != "quex" {
+        return (vec![], vec![], None);
+    }
+
+    // keyed by the canonical path, the same whichever directory quex runs from
+    let cache = cache.and_then(|cache| {
+        let key = fs::canonicalize(path).ok()?;
+        let modified = fs::metadata(&key).and_then(|meta| meta.modified()).ok()?;
+        Some((cache, key, modified))
+    });
+    if let Some(cached) = cache
+        .as_ref()
+        .and_then(|(cache, key, modified)| cache.get(key, *modified))
+    {
+        let (schedules, errors) = expand(path, cached.entries(), options, walked);
+        return (schedules, errors, None);
+    }
+
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => return (vec![], vec![io_error(path.to_path_buf(), e)], None),
+    };
+
+    let lines = || source_lines(path, file_extension, format, &source, options);
+
+    let Some((cache, key, modified)) = cache else {
+        let (schedules, errors) = parse_lines(path, lines(), options, walked, &[path]);
+        return (schedules, errors, None);
+    };
+
+    let hash = cache::fnv1a(source.as_bytes());
+    let file = match cache.get_by_hash(&key, hash) {
+        Some(cached) => cached.touched(modified),
+        None => CachedFile::new(
+            modified,
+            hash,
+            lines().into_iter().map(Entry::new).collect(),
+        ),
+    };
+
+    let (schedules, errors) = expand(path, file.entries(), options, walked);
+    (schedules, errors, Some((key, file)))
+}
 
-            for (line_number, line) in reader.lines().enumerate() {
-                let line = match line {
-                    Ok(line) => line,
-                    Err(e) => return Err(io::FileError::new(path, e)),
-                };
+/// The schedules of the calendar piped to stdin, read as a file of the
+/// `stdin_format` extension.
+fn read_stdin(options: &WalkOptions, walked: &HashSet<PathBuf>) -> (Schedules, QErrors) {
+    let path = Path::new(STDIN_NAME);
+
+    let source = match std::io::read_to_string(std::io::stdin()) {
+        Ok(source) => source,
+        Err(e) => return (vec![], vec![io_error(path.to_path_buf(), e)]),
+    };
+
+    let extension = options.stdin_format.as_str();
+    let format = options.file_formats.get(extension);
+    let lines = source_lines(path, extension, format, &source, options);
+    let (mut schedules, errors) = parse_lines(path, lines, options, walked, &[path]);
+
+    // there is no file to open the events in, unlike the included ones
+    for sch in &mut schedules {
+        if sch.source_path.as_deref() == Some(path) {
+            sch.source_path = None;
+        }
+    }
+
+    (schedules, errors)
+}
+
+/// The quex lines of a file, every line of a `.quex` file, which has no
+/// `format`. Blank lines and comments are left out.
+fn source_lines(
+    path: &Path,
+    extension: &str,
+    format: Option<&FileFormat>,
+    source: &str,
+    options: &WalkOptions,
+) -> Vec<QuexLine> {
+    let mut lines: Vec<QuexLine> = match format {
+        Some(format) => format_lines(path, extension, format, source, options),
+        None => source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| QuexLine {
+                line_number: i + 1,
+                text: line.to_string(),
+                source: None,
+                category: None,
+            })
+            .collect(),
+    };
+
+    lines.retain(|line| !parser::is_ignored(&line.text));
+    lines
+}
+
+/// The schedules of the lines of a file, along with the ones of the files it
+/// includes. `walked` holds the canonical paths of the files the walk reads,
+/// and `including` the file and the files that included it.
+fn parse_lines(
+    path: &Path,
+    lines: Vec<QuexLine>,
+    options: &WalkOptions,
+    walked: &HashSet<PathBuf>,
+    including: &[&Path],
+) -> (Schedules, QErrors) {
+    let mut schedules = vec![];
+    let mut errors = Error::new(path.to_path_buf());
+    let mut included_errors = vec![];
+
+    for line in lines {
+        let Some(target) = include::directive(&line.text) else {
+            parse_into(path, line, &mut schedules, &mut errors);
+            continue;
+        };
+
+        let (schs, errs, problems) = read_include(path, target, options, walked, including);
+        schedules.extend(schs);
+        included_errors.extend(errs);
+
+        for problem in problems {
+            errors.push(ValueError::new(
+                LineError::Include(problem),
+                line.line_number,
+                line.text.clone(),
+            ));
+        }
+    }
+
+    let (schedules, mut errors) = into_results(schedules, errors);
+    errors.extend(included_errors);
+    (schedules, errors)
+}
 
-                if line.is_empty() {
-                    continue;
-                }
+/// The schedules of the files an include directive of `path` refers to, and
+/// the problems to report on the directive. A file of no known format is a
+/// problem, unless it was matched by a glob, and a file the walk reads is left
+/// to it, so that its events aren't read twice.
+fn read_include(
+    path: &Path,
+    target: &str,
+    options: &WalkOptions,
+    walked: &HashSet<PathBuf>,
+    including: &[&Path],
+) -> (Schedules, QErrors, Vec<String>) {
+    let mut schedules = vec![];
+    let mut errors = vec![];
+    let mut problems = vec![];
 
-                match parser::parse_line(line.as_str()) {
-                    Ok(event) => schedules.push(event),
-                    Err(e) => errors.push(ValueError::new(e, line_number + 1, line)),
-                }
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let files = match include::resolve(dir, target) {
+        Ok(files) => files,
+        Err(e) => return (schedules, errors, vec![e]),
+    };
+    let single = !include::is_glob(target);
+
+    let including_canonical: Vec<PathBuf> = including
+        .iter()
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect();
+
+    for file in &files {
+        let extension = file.extension().and_then(OsStr::to_str).unwrap_or("");
+        let format = options.file_formats.get(extension);
+
+        if format.is_none() && extension != "quex" {
+            if single {
+                problems.push(format!("`{}` is of no known format", file.display()));
             }
+            continue;
+        }
 
-            let rec_is_hard = if errors.is_empty() {
-                Vec::with_capacity(0)
-            } else {
-                vec![errors]
-            };
+        // read by its canonical path, which is reported rather than `../`s
+        let file = match fs::canonicalize(file) {
+            Ok(file) => file,
+            Err(e) => {
+                problems.push(format!("can't read `{}`: {e}", file.display()));
+                continue;
+            }
+        };
+        if including_canonical.contains(&file) {
+            problems.push(format!("include cycle through `{}`", file.display()));
+            continue;
+        }
+        if walked.contains(&file) {
+            continue;
+        }
 
-            Ok((schedules, rec_is_hard))
-        } else {
-            // NOTE: Should I return an Option here ?
-            return Ok((vec![], vec![]));
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(e) => {
+                problems.push(format!("can't read `{}`: {e}", file.display()));
+                continue;
+            }
+        };
+
+        let lines = source_lines(&file, extension, format, &source, options);
+        let including = [including, &[&file]].concat();
+        let (schs, errs) = parse_lines(&file, lines, options, walked, &including);
+        schedules.extend(schs);
+        errors.extend(errs);
+    }
+
+    (schedules, errors, problems)
+}
+
+/// The schedules of the cached entries of a file, the lines are parsed again,
+/// and the files they include read again.
+fn expand(
+    path: &Path,
+    entries: &[Entry],
+    options: &WalkOptions,
+    walked: &HashSet<PathBuf>,
+) -> (Schedules, QErrors) {
+    let today = time::OffsetDateTime::now_utc().to_julian_day();
+    let mut fixed = vec![];
+    let mut lines = vec![];
+
+    for entry in entries {
+        match entry {
+            Entry::Line {
+                line_number,
+                text,
+                source,
+                category,
+            } => lines.push(QuexLine {
+                line_number: *line_number,
+                text: text.clone(),
+                source: source.clone(),
+                category: category.clone(),
+            }),
+            entry => fixed.extend(cache::fixed_schedule(entry, path, today)),
         }
     }
+
+    let (schedules, errors) = parse_lines(path, lines, options, walked, &[path]);
+    fixed.extend(schedules);
+    (fixed, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs, path::Path};
+
+    use super::{read_file, walk_dirs, WalkOptions};
+    use crate::calender::LineError;
+
+    /// The descriptions and include problems of a file, read with no walk.
+    fn read(path: &Path) -> (Vec<String>, Vec<(String, usize, String)>) {
+        let (schedules, errors, _) =
+            read_file(path, &WalkOptions::default(), &HashSet::new(), None);
+
+        let descriptions = schedules.into_iter().map(|sch| sch.description).collect();
+        let problems = errors
+            .iter()
+            .flat_map(|error| {
+                error.errors().iter().filter_map(|value_error| {
+                    let LineError::Include(problem) = value_error.line_error() else {
+                        return None;
+                    };
+                    let name = error.path().file_name()?.to_string_lossy().to_string();
+                    Some((name, value_error.line_number(), problem.clone()))
+                })
+            })
+            .collect();
+
+        (descriptions, problems)
+    }
+
+    #[test]
+    fn include_cycles() {
+        let dir = std::env::temp_dir().join(format!("quex-cycles-{}", std::process::id()));
+        fs::create_dir_all(dir.join("parts")).unwrap();
+        let write = |file: &str, source: &str| fs::write(dir.join(file), source).unwrap();
+
+        write("self.quex", "@include self.quex\njan 1 2025, self\n");
+        write("a.quex", "jan 1 2025, a\n@include b.quex\n");
+        write("b.quex", "@include a.quex\njan 2 2025, b\n");
+        write("glob.quex", "@include parts/*.quex\n");
+        write("parts/1.quex", "jan 3 2025, one\n");
+        write("parts/2.quex", "@include ../glob.quex\n");
+        write("parts/3.quex", "jan 4 2025, three\n");
+
+        let cycle = |file: &str| format!("include cycle through `{}`", dir.join(file).display());
+
+        assert_eq!(
+            read(&dir.join("self.quex")),
+            (
+                vec!["self".to_string()],
+                vec![("self.quex".to_string(), 1, cycle("self.quex"))]
+            )
+        );
+        assert_eq!(
+            read(&dir.join("a.quex")),
+            (
+                vec!["a".to_string(), "b".to_string()],
+                vec![("b.quex".to_string(), 1, cycle("a.quex"))]
+            )
+        );
+        // the other matches of the glob are still read
+        assert_eq!(
+            read(&dir.join("glob.quex")),
+            (
+                vec!["one".to_string(), "three".to_string()],
+                vec![("2.quex".to_string(), 1, cycle("glob.quex"))]
+            )
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn overlapping_roots() {
+        let dir = std::env::temp_dir().join(format!("quex-roots-{}", std::process::id()));
+        fs::create_dir_all(dir.join("w")).unwrap();
+        fs::write(dir.join("w/v.quex"), "jan 1 2025, once\n").unwrap();
+
+        let roots = [
+            dir.join("w"),
+            dir.join("w/v.quex"),
+            dir.join("w/../w/v.quex"),
+        ];
+        let (schedules, errors) = walk_dirs(&roots, &WalkOptions::default());
+
+        assert!(errors.is_empty());
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].source_path, Some(dir.join("w/v.quex")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,93 @@
+// Agenda layout, the events grouped under a header per day:
+//
+// Mon, Jan 6 — Today
+//   Play soccer with the boys.
+//   Pay bills.
+
+use super::{
+    describe,
+    style::{Palette, Style},
+};
+use crate::{Schedule, Schedules};
+
+/// The schedules of every day that has some, in the order they were given.
+/// The schedules are expected to be sorted by date, as `filter_schedules`
+/// leaves them.
+pub fn group_by_day(schedules: &Schedules) -> Vec<(i32, Vec<&Schedule>)> {
+    let mut days: Vec<(i32, Vec<&Schedule>)> = vec![];
+
+    for sch in schedules {
+        match days.last_mut() {
+            Some((day, group)) if *day == sch.julian_day_number => group.push(sch),
+            _ => days.push((sch.julian_day_number, vec![sch])),
+        }
+    }
+
+    days
+}
+
+/// `Mon, Jan 6`
+pub fn day_title(julian_day_number: i32) -> String {
+    let Ok(date) = time::Date::from_julian_day(julian_day_number) else {
+        return julian_day_number.to_string();
+    };
+
+    let short = |name: String| name.chars().take(3).collect::<String>();
+    format!(
+        "{}, {} {}",
+        short(date.weekday().to_string()),
+        short(date.month().to_string()),
+        date.day()
+    )
+}
+
+/// The day title, `Mon, Jan 6 — Today` for the days next to today.
+pub fn day_header(julian_day_number: i32) -> String {
+    let title = day_title(julian_day_number);
+
+    let today = time::OffsetDateTime::now_utc().to_julian_day();
+    match julian_day_number - today {
+        0 => format!("{title} — Today"),
+        1 => format!("{title} — Tomorrow"),
+        -1 => format!("{title} — Yesterday"),
+        _ => title,
+    }
+}
+
+pub fn format(schedules: &Schedules, week_separators: bool, palette: &Palette) -> String {
+    let today = time::OffsetDateTime::now_utc().to_julian_day();
+    let mut out = String::new();
+    let mut last_week = None;
+
+    for (day, group) in group_by_day(schedules) {
+        let week = time::Date::from_julian_day(day)
+            .map(|date| date.to_iso_week_date())
+            .map(|(year, week, _)| (year, week))
+            .ok();
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        if week_separators && week != last_week {
+            let (_, iso_week) = week.unwrap_or_default();
+            out.push_str(&format!("── Week {iso_week} ──\n\n"));
+        }
+        last_week = week;
+
+        let header = Style {
+            bold: true,
+            reverse: day == today,
+            ..Style::default()
+        };
+        out.push_str(&palette.paint(header, &day_header(day)));
+        out.push('\n');
+
+        for sch in group {
+            let line = format!("  {}", describe(sch));
+            out.push_str(&palette.paint(palette.style(sch), &line));
+            out.push('\n');
+        }
+    }
+
+    out
+}
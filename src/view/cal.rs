@@ -0,0 +1,82 @@
+// `quex cal`, a month grid with the days that have events marked, followed
+// by the events of the month.
+
+use super::{
+    describe,
+    grid::MonthGrid,
+    style::{Palette, Style},
+};
+use crate::Schedules;
+
+const CELL: usize = 4;
+
+pub fn format(grid: &MonthGrid, schedules: &Schedules, palette: &Palette) -> String {
+    let mut schedules: Vec<_> = schedules
+        .iter()
+        .filter_map(|sch| Some((grid.occurrence(sch)?, sch)))
+        .collect();
+    schedules.sort_by_key(|(jdn, _)| *jdn);
+
+    let has_events = |day: u8| {
+        schedules
+            .iter()
+            .any(|(jdn, _)| *jdn == grid.julian_day_of(day))
+    };
+
+    let today = time::OffsetDateTime::now_utc().to_julian_day();
+    let width = CELL * 7;
+    let title_len = grid.title.chars().count();
+    let mut out = format!(
+        "{}{}\n",
+        " ".repeat(width.saturating_sub(title_len) / 2),
+        grid.title
+    );
+
+    let header: String = grid
+        .weekdays
+        .iter()
+        .map(|weekday| format!("{:>w$} ", weekday, w = CELL - 1))
+        .collect();
+
+    out.push_str(header.trim_end());
+    out.push('\n');
+
+    for week in grid.weeks() {
+        let line: String = week
+            .iter()
+            .map(|cell| {
+                let Some(day) = *cell else {
+                    return " ".repeat(CELL);
+                };
+
+                let style = Style {
+                    bold: has_events(day),
+                    reverse: grid.julian_day_of(day) == today,
+                    ..Style::default()
+                };
+                let mark = if has_events(day) { '*' } else { ' ' };
+
+                let day = day.to_string();
+                let padding = " ".repeat(CELL - 1 - day.len());
+
+                format!("{}{}{}", padding, palette.paint(style, &day), mark)
+            })
+            .collect();
+
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    if !schedules.is_empty() {
+        out.push('\n');
+    }
+
+    for (jdn, sch) in schedules {
+        let line = format!("{:>w$}  {}", grid.day_of(jdn), describe(sch), w = CELL - 1);
+
+        out.push_str(&palette.paint(palette.style(sch), &line));
+        out.push('\n');
+    }
+
+    out
+}
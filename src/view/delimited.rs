@@ -0,0 +1,76 @@
+// CSV and TSV output, meant to be loaded into spreadsheets.
+//
+// Fields are quoted following RFC 4180: a field containing the delimiter, a
+// double quote or a line break is wrapped in double quotes and any double quote
+// inside it is doubled. Descriptions often contain commas (the grammar only
+// splits on the first one) and so does the human readable date.
+
+use crate::Schedules;
+
+const HEADER: [&str; 6] = [
+    "date",
+    "iso_date",
+    "diff",
+    "description",
+    "source_path",
+    "category",
+];
+
+pub fn format(schedules: &Schedules, delimiter: char) -> String {
+    let mut out = record(HEADER.map(String::from), delimiter);
+
+    for sch in schedules {
+        let source_path = sch
+            .source_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        out.push_str(&record(
+            [
+                sch.date.clone(),
+                sch.iso_date(),
+                sch.diff.to_string(),
+                sch.description.clone(),
+                source_path,
+                sch.category.clone().unwrap_or_default(),
+            ],
+            delimiter,
+        ));
+    }
+
+    out
+}
+
+fn record<const N: usize>(fields: [String; N], delimiter: char) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| quote(field, delimiter))
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string());
+
+    line.push('\n');
+    line
+}
+
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quote;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("plain", ','), "plain");
+        assert_eq!(quote("one, two", ','), "\"one, two\"");
+        assert_eq!(quote("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("one, two", '\t'), "one, two");
+        assert_eq!(quote("one\ttwo", '\t'), "\"one\ttwo\"");
+    }
+}
@@ -0,0 +1,288 @@
+// A month laid out in weeks the way `cal(1)` draws it, Sunday first. Both the
+// Gregorian and the Ethiopian week start on Sunday (እሑድ), so the layout only
+// differs in the number of months and their lengths, e.g. ጳጉሜ has 5 or 6 days.
+
+use crate::{
+    calender::{CalendarKind, Recurrence},
+    Schedule,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthGrid {
+    pub calendar: CalendarKind,
+    pub title: String,
+    pub weekdays: [String; 7],
+    /// The julian day number of the first day of the month.
+    pub first: i32,
+    pub days: u8,
+}
+
+impl MonthGrid {
+    /// A month of the given calendar, the current year and month are used for
+    /// whichever of `year` and `month` is missing.
+    pub fn new(
+        calendar: CalendarKind,
+        year: Option<i32>,
+        month: Option<u8>,
+    ) -> Result<Self, String> {
+        match calendar {
+            CalendarKind::Gregorian => {
+                let today = time::OffsetDateTime::now_utc();
+                Self::gregorian(
+                    year.unwrap_or(today.year()),
+                    month.unwrap_or(today.month() as u8),
+                )
+            }
+            #[cfg(feature = "eth")]
+            CalendarKind::Ethiopian => {
+                let today = zemen::Zemen::today();
+                Self::ethiopian(
+                    year.unwrap_or(today.year()),
+                    month.unwrap_or(today.month() as u8),
+                )
+            }
+            #[cfg(not(feature = "eth"))]
+            CalendarKind::Ethiopian => Err(no_eth()),
+        }
+    }
+
+    fn gregorian(year: i32, month: u8) -> Result<Self, String> {
+        let month = time::Month::try_from(month).map_err(|e| e.to_string())?;
+        let first = time::Date::from_calendar_date(year, month, 1).map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            calendar: CalendarKind::Gregorian,
+            title: format!("{} {}", month, year),
+            weekdays: ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"].map(String::from),
+            first: first.to_julian_day(),
+            days: time::util::days_in_year_month(year, month),
+        })
+    }
+
+    #[cfg(feature = "eth")]
+    fn ethiopian(year: i32, month: u8) -> Result<Self, String> {
+        use zemen::{Samint, Werh, Zemen};
+
+        let werh = Werh::try_from(month).map_err(|e| e.to_string())?;
+        let first = Zemen::from_eth_cal(year, werh, 1).map_err(|e| e.to_string())?;
+        let next = match werh {
+            Werh::Puagme => Zemen::from_eth_cal(year + 1, Werh::Meskerem, 1),
+            _ => Zemen::from_eth_cal(year, werh.next(), 1),
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            calendar: CalendarKind::Ethiopian,
+            title: format!("{} {}", werh, year),
+            weekdays: [0, 1, 2, 3, 4, 5, 6].map(|day| {
+                Samint::try_from(day)
+                    .unwrap()
+                    .to_string()
+                    .chars()
+                    .take(2)
+                    .collect()
+            }),
+            first: first.to_jdn(),
+            days: (next.to_jdn() - first.to_jdn()) as u8,
+        })
+    }
+
+    /// The month of the given calendar a julian day number falls in.
+    pub fn containing(calendar: CalendarKind, julian_day_number: i32) -> Result<Self, String> {
+        match calendar {
+            CalendarKind::Gregorian => {
+                let date =
+                    time::Date::from_julian_day(julian_day_number).map_err(|e| e.to_string())?;
+                Self::gregorian(date.year(), date.month() as u8)
+            }
+            #[cfg(feature = "eth")]
+            CalendarKind::Ethiopian => {
+                let date = zemen::Zemen::from_jdn(julian_day_number).map_err(|e| e.to_string())?;
+                Self::ethiopian(date.year(), date.month() as u8)
+            }
+            #[cfg(not(feature = "eth"))]
+            CalendarKind::Ethiopian => Err(no_eth()),
+        }
+    }
+
+    /// Every month of the given calendar from the one containing `begin` to the
+    /// one containing `end`.
+    pub fn between(calendar: CalendarKind, begin: i32, end: i32) -> Result<Vec<Self>, String> {
+        let mut months = vec![Self::containing(calendar, begin)?];
+
+        while let Some(month) = months.last().filter(|month| month.last() < end) {
+            months.push(Self::containing(calendar, month.last() + 1)?);
+        }
+
+        Ok(months)
+    }
+
+    pub fn last(&self) -> i32 {
+        self.first + self.days as i32 - 1
+    }
+
+    pub fn contains(&self, julian_day_number: i32) -> bool {
+        (self.first..=self.last()).contains(&julian_day_number)
+    }
+
+    /// The day of the month of a julian day number inside the month.
+    pub fn day_of(&self, julian_day_number: i32) -> u8 {
+        (julian_day_number - self.first + 1) as u8
+    }
+
+    pub fn julian_day_of(&self, day: u8) -> i32 {
+        self.first + day as i32 - 1
+    }
+
+    /// The julian day number a schedule falls on inside the month, recurring
+    /// events on the day they repeat on rather than only their next occurrence.
+    pub fn occurrence(&self, sch: &Schedule) -> Option<i32> {
+        if sch.recurrence == Recurrence::Once {
+            return Some(sch.julian_day_number).filter(|&jdn| self.contains(jdn));
+        }
+
+        // recurring events repeat in the calendar they were written in
+        let (month, day) = month_day(sch.calendar, sch.julian_day_number)?;
+
+        (self.first..=self.last()).find(|&jdn| match month_day(sch.calendar, jdn) {
+            Some((_, d)) if sch.recurrence == Recurrence::Monthly => d == day,
+            Some(date) => date == (month, day),
+            None => false,
+        })
+    }
+
+    /// The days of the month, a row per week, with blanks before the first and
+    /// after the last day.
+    pub fn weeks(&self) -> Vec<[Option<u8>; 7]> {
+        // julian day 0 is a Monday
+        let offset = ((self.first + 1) % 7) as usize;
+
+        let mut cells: Vec<Option<u8>> = vec![None; offset];
+        cells.extend((1..=self.days).map(Some));
+        cells.resize(cells.len().div_ceil(7) * 7, None);
+
+        cells
+            .chunks(7)
+            .map(|week| week.try_into().unwrap())
+            .collect()
+    }
+}
+
+/// The month and day of a julian day number in the given calendar.
+fn month_day(calendar: CalendarKind, julian_day_number: i32) -> Option<(u8, u8)> {
+    match calendar {
+        CalendarKind::Gregorian => time::Date::from_julian_day(julian_day_number)
+            .ok()
+            .map(|date| (date.month() as u8, date.day())),
+        #[cfg(feature = "eth")]
+        CalendarKind::Ethiopian => zemen::Zemen::from_jdn(julian_day_number)
+            .ok()
+            .map(|date| (date.month() as u8, date.day())),
+        #[cfg(not(feature = "eth"))]
+        CalendarKind::Ethiopian => None,
+    }
+}
+
+#[cfg(not(feature = "eth"))]
+fn no_eth() -> String {
+    "the Ethiopian calendar needs the `eth` feature".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MonthGrid;
+    use crate::{
+        calender::{CalendarKind, Recurrence},
+        Schedule,
+    };
+
+    #[test]
+    fn gregorian_layout() {
+        // January 2025 starts on a Wednesday
+        let grid = MonthGrid::new(CalendarKind::Gregorian, Some(2025), Some(1)).unwrap();
+        let weeks = grid.weeks();
+
+        assert_eq!(grid.days, 31);
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(
+            weeks[0],
+            [None, None, None, Some(1), Some(2), Some(3), Some(4)]
+        );
+        assert_eq!(
+            weeks[4],
+            [
+                Some(26),
+                Some(27),
+                Some(28),
+                Some(29),
+                Some(30),
+                Some(31),
+                None
+            ]
+        );
+        assert_eq!(grid.julian_day_of(1), 2460677);
+    }
+
+    #[test]
+    fn months_between() {
+        // December 31, 2024 to March 1, 2025
+        let months = MonthGrid::between(CalendarKind::Gregorian, 2460676, 2460736).unwrap();
+        let titles: Vec<_> = months.iter().map(|m| m.title.as_str()).collect();
+
+        assert_eq!(
+            titles,
+            [
+                "December 2024",
+                "January 2025",
+                "February 2025",
+                "March 2025"
+            ]
+        );
+    }
+
+    #[test]
+    fn recurring_occurrences() {
+        // January 31, 2025
+        let schedule = |recurrence| Schedule {
+            description: "Pay rent".to_string(),
+            julian_day_number: 2460707,
+            diff: 0,
+            date: "January 31, 2025".to_string(),
+            source_path: None,
+            line_number: None,
+            calendar: CalendarKind::Gregorian,
+            recurrence,
+            raw: String::new(),
+            category: None,
+        };
+        let month =
+            |month| MonthGrid::new(CalendarKind::Gregorian, Some(2025), Some(month)).unwrap();
+        let day = |month: &MonthGrid, sch| month.occurrence(&sch).map(|jdn| month.day_of(jdn));
+
+        assert_eq!(day(&month(1), schedule(Recurrence::Once)), Some(31));
+        assert_eq!(day(&month(3), schedule(Recurrence::Once)), None);
+        assert_eq!(day(&month(3), schedule(Recurrence::Monthly)), Some(31));
+        // February has no 31st
+        assert_eq!(day(&month(2), schedule(Recurrence::Monthly)), None);
+        assert_eq!(day(&month(3), schedule(Recurrence::Yearly)), None);
+
+        let january = MonthGrid::new(CalendarKind::Gregorian, Some(2027), Some(1)).unwrap();
+        assert_eq!(day(&january, schedule(Recurrence::NamedYearly)), Some(31));
+    }
+
+    #[test]
+    #[cfg(feature = "eth")]
+    fn ethiopian_layout() {
+        // ጳጉሜ 2015 is a leap month, 2015 % 4 == 3
+        let pagume = MonthGrid::new(CalendarKind::Ethiopian, Some(2015), Some(13)).unwrap();
+        assert_eq!(pagume.days, 6);
+
+        let pagume = MonthGrid::new(CalendarKind::Ethiopian, Some(2016), Some(13)).unwrap();
+        assert_eq!(pagume.days, 5);
+
+        let meskerem = MonthGrid::new(CalendarKind::Ethiopian, Some(2017), Some(1)).unwrap();
+        assert_eq!(meskerem.days, 30);
+        // መስከረም 1, 2017 is Wednesday, September 11, 2024
+        assert_eq!(meskerem.weeks()[0][3], Some(1));
+    }
+}
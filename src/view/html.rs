@@ -0,0 +1,154 @@
+// `quex export html`, a self-contained static page with the month grids of
+// every calendar quex was built with, followed by an agenda of the events.
+
+use super::{agenda, describe, grid::MonthGrid};
+use crate::{calender::CalendarKind, Schedules};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+h1 { font-size: 1.6em; }
+h2 { margin-top: 2em; border-bottom: 1px solid #ccc; }
+.months { display: flex; flex-wrap: wrap; gap: 1.5em; }
+table.month { border-collapse: collapse; }
+table.month caption { font-weight: bold; padding: .3em; }
+table.month th, table.month td { width: 2.6em; height: 2.2em; text-align: center; vertical-align: top; border: 1px solid #eee; }
+table.month td.events { background: #e8f0fe; font-weight: bold; }
+table.month td.today { outline: 2px solid #1a73e8; }
+.agenda h3 { margin-bottom: .2em; }
+.agenda ul { margin-top: 0; }
+.agenda .past { color: #888; }
+.date, .source { color: #888; font-size: .8em; }
+";
+
+pub fn format(schedules: &Schedules) -> String {
+    let today = time::OffsetDateTime::now_utc().to_julian_day();
+    let begin = schedules.iter().map(|sch| sch.julian_day_number).min();
+    let end = schedules.iter().map(|sch| sch.julian_day_number).max();
+
+    let mut body = String::from("<h1>quex</h1>\n");
+
+    for calendar in calendars() {
+        let months =
+            match MonthGrid::between(calendar, begin.unwrap_or(today), end.unwrap_or(today)) {
+                Ok(months) => months,
+                Err(_) => continue,
+            };
+
+        body.push_str(&format!("<h2>{} calendar</h2>\n", title_case(calendar)));
+        body.push_str("<div class=\"months\">\n");
+        for month in months {
+            body.push_str(&month_table(&month, schedules, today));
+        }
+        body.push_str("</div>\n");
+    }
+
+    body.push_str("<h2>Agenda</h2>\n<div class=\"agenda\">\n");
+    for (day, group) in agenda::group_by_day(schedules) {
+        body.push_str(&format!(
+            "<h3>{}</h3>\n<ul>\n",
+            escape(&agenda::day_header(day))
+        ));
+
+        for sch in group {
+            let class = if sch.diff < 0 { " class=\"past\"" } else { "" };
+            let source = match (&sch.source_path, sch.line_number) {
+                (Some(path), Some(line)) => format!(
+                    " <span class=\"source\">{}:{}</span>",
+                    escape(&path.to_string_lossy()),
+                    line
+                ),
+                _ => String::new(),
+            };
+
+            body.push_str(&format!(
+                "<li{class}>{} <span class=\"date\">({})</span>{source}</li>\n",
+                escape(&describe(sch)),
+                escape(&sch.date),
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+    body.push_str("</div>\n");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>quex</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+fn month_table(month: &MonthGrid, schedules: &Schedules, today: i32) -> String {
+    let mut table = format!(
+        "<table class=\"month\">\n<caption>{}</caption>\n<tr>",
+        escape(&month.title)
+    );
+
+    for weekday in &month.weekdays {
+        table.push_str(&format!("<th>{}</th>", escape(weekday)));
+    }
+    table.push_str("</tr>\n");
+
+    let occurrences: Vec<_> = schedules
+        .iter()
+        .filter_map(|sch| Some((month.occurrence(sch)?, sch)))
+        .collect();
+
+    for week in month.weeks() {
+        table.push_str("<tr>");
+
+        for cell in week {
+            let Some(day) = cell else {
+                table.push_str("<td></td>");
+                continue;
+            };
+
+            let jdn = month.julian_day_of(day);
+            let events: Vec<_> = occurrences
+                .iter()
+                .filter(|(day, _)| *day == jdn)
+                .map(|(_, sch)| escape(&sch.description))
+                .collect();
+
+            let mut classes = vec![];
+            if !events.is_empty() {
+                classes.push("events");
+            }
+            if jdn == today {
+                classes.push("today");
+            }
+
+            table.push_str(&format!(
+                "<td class=\"{}\" title=\"{}\">{}</td>",
+                classes.join(" "),
+                events.join("&#10;"),
+                day
+            ));
+        }
+
+        table.push_str("</tr>\n");
+    }
+
+    table.push_str("</table>\n");
+    table
+}
+
+fn calendars() -> Vec<CalendarKind> {
+    #[cfg(feature = "eth")]
+    return vec![CalendarKind::Gregorian, CalendarKind::Ethiopian];
+    #[cfg(not(feature = "eth"))]
+    return vec![CalendarKind::Gregorian];
+}
+
+fn title_case(calendar: CalendarKind) -> &'static str {
+    match calendar {
+        CalendarKind::Gregorian => "Gregorian",
+        CalendarKind::Ethiopian => "Ethiopian",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,273 @@
+// The JSON schema of `--format json`, of the envelope printed with
+// `--envelope` and of the records of `--format jsonl`.
+//
+// It is kept apart from `Schedule` so the internal representation can change
+// without breaking consumers. Any change to the fields below, other than
+// adding a new one, must bump `VERSION`.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use pest::error::InputLocation;
+
+use serde_derive::Serialize;
+
+use time::format_description::well_known::Rfc3339;
+
+use crate::{
+    calender::{CalendarKind, LineError, Recurrence},
+    error::Error,
+    filter::Window,
+    QErrors, Schedule, Schedules,
+};
+
+pub const VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct Document<'a> {
+    version: u32,
+    events: Vec<Event<'a>>,
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    generated_at: String,
+    window: JsonWindow,
+    events: Vec<Event<'a>>,
+    errors: Vec<ErrorRecord<'a>>,
+}
+
+/// A line of `--format jsonl`, tagged with its `type`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Event(Event<'a>),
+    Error(ErrorRecord<'a>),
+}
+
+#[derive(Serialize)]
+struct JsonWindow {
+    begin: Option<String>,
+    end: Option<String>,
+    begin_julian_day_number: Option<i32>,
+    end_julian_day_number: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct Event<'a> {
+    description: &'a str,
+    date: &'a str,
+    iso_date: String,
+    julian_day_number: i32,
+    diff: i32,
+    calendar: CalendarKind,
+    recurrence: Recurrence,
+    raw: &'a str,
+    source_path: Option<&'a Path>,
+    line_number: Option<usize>,
+    category: Option<&'a str>,
+}
+
+/// A line that couldn't be parsed, or a file that couldn't be read, in which
+/// case only `path` and `io_error` are set.
+#[derive(Serialize)]
+pub struct ErrorRecord<'a> {
+    path: &'a Path,
+    line_number: Option<usize>,
+    /// One of `cant_parse_input`, `invalid_value`, `parsing_error`, `include`
+    /// or `io`.
+    kind: &'static str,
+    message: String,
+    line: Option<&'a str>,
+    /// The 1-based column a `parsing_error` was found at.
+    column: Option<usize>,
+    io_error: Option<&'a str>,
+}
+
+/// The records of the errors of every file, one per line.
+fn error_records(errors: &QErrors) -> Vec<ErrorRecord<'_>> {
+    errors.iter().flat_map(file_error_records).collect()
+}
+
+fn file_error_records(error: &Error) -> Vec<ErrorRecord<'_>> {
+    if let Some(io_error) = error.io_error() {
+        return vec![ErrorRecord {
+            path: error.path(),
+            line_number: None,
+            kind: "io",
+            message: format!("can't read: {io_error}"),
+            line: None,
+            column: None,
+            io_error: Some(io_error),
+        }];
+    }
+
+    error
+        .errors()
+        .iter()
+        .map(|value_error| {
+            let (kind, message, column) = match value_error.line_error() {
+                LineError::CantParseInput => {
+                    ("cant_parse_input", "can't parse input.".to_string(), None)
+                }
+                LineError::InvalidValue(message) => ("invalid_value", message.clone(), None),
+                LineError::ParsingError {
+                    message, column, ..
+                } => {
+                    let position = match **column {
+                        InputLocation::Pos(position) => position,
+                        InputLocation::Span((start, _)) => start,
+                    };
+                    ("parsing_error", message.clone(), Some(position + 1))
+                }
+                LineError::Include(message) => {
+                    ("include", format!("can't include: {message}"), None)
+                }
+            };
+
+            ErrorRecord {
+                path: error.path(),
+                line_number: Some(value_error.line_number()),
+                kind,
+                message,
+                line: Some(value_error.line()),
+                column,
+                io_error: None,
+            }
+        })
+        .collect()
+}
+
+impl<'a> From<&'a Schedule> for Event<'a> {
+    fn from(sch: &'a Schedule) -> Self {
+        Self {
+            description: &sch.description,
+            date: &sch.date,
+            iso_date: sch.iso_date(),
+            julian_day_number: sch.julian_day_number,
+            diff: sch.diff,
+            calendar: sch.calendar,
+            recurrence: sch.recurrence,
+            raw: &sch.raw,
+            source_path: sch.source_path.as_deref(),
+            line_number: sch.line_number,
+            category: sch.category.as_deref(),
+        }
+    }
+}
+
+impl From<Window> for JsonWindow {
+    fn from(window: Window) -> Self {
+        let iso = |jdn| {
+            time::Date::from_julian_day(jdn)
+                .map(|date| date.to_string())
+                .ok()
+        };
+
+        Self {
+            begin: window.begin.and_then(iso),
+            end: window.end.and_then(iso),
+            begin_julian_day_number: window.begin,
+            end_julian_day_number: window.end,
+        }
+    }
+}
+
+pub fn format(schedules: &Schedules) -> String {
+    let document = Document {
+        version: VERSION,
+        events: schedules.iter().map(Event::from).collect(),
+    };
+
+    serde_json::to_string(&document).unwrap()
+}
+
+pub fn envelope(schedules: &Schedules, errors: &QErrors, window: Window) -> String {
+    let envelope = Envelope {
+        version: VERSION,
+        generated_at: time::OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        window: window.into(),
+        events: schedules.iter().map(Event::from).collect(),
+        errors: error_records(errors),
+    };
+
+    serde_json::to_string(&envelope).unwrap()
+}
+
+/// Writes one event per line, once the calendar has been walked and sorted.
+pub fn write_event_lines(schedules: &Schedules, out: impl Write) -> io::Result<()> {
+    write_lines(schedules.iter().map(|sch| Record::Event(sch.into())), out)
+}
+
+/// The errors as a JSON array, printed on stderr by `--format json`.
+pub fn format_errors(errors: &QErrors) -> String {
+    serde_json::to_string(&error_records(errors)).unwrap()
+}
+
+pub fn write_error_lines(errors: &QErrors, out: impl Write) -> io::Result<()> {
+    write_lines(error_records(errors).into_iter().map(Record::Error), out)
+}
+
+fn write_lines<'a>(records: impl Iterator<Item = Record<'a>>, out: impl Write) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+    for record in records {
+        serde_json::to_writer(&mut out, &record)?;
+        out.write_all(b"\n")?;
+    }
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::format_errors;
+    use crate::{
+        calender::LineError,
+        error::{io::FileError, Error, ValueError},
+    };
+
+    #[test]
+    fn error_records() {
+        let mut error = Error::new(PathBuf::from("work.quex"));
+        error.push(ValueError::new(
+            LineError::InvalidValue("day can't be greater than 31".to_string()),
+            4,
+            "jan 32 2025, review".to_string(),
+        ));
+
+        let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let io_error: Error = FileError::new(PathBuf::from("private.md"), not_found).into();
+
+        let records: serde_json::Value =
+            serde_json::from_str(&format_errors(&vec![error, io_error])).unwrap();
+
+        assert_eq!(
+            records,
+            serde_json::json!([
+                {
+                    "path": "work.quex",
+                    "line_number": 4,
+                    "kind": "invalid_value",
+                    "message": "day can't be greater than 31",
+                    "line": "jan 32 2025, review",
+                    "column": null,
+                    "io_error": null,
+                },
+                {
+                    "path": "private.md",
+                    "line_number": null,
+                    "kind": "io",
+                    "message": "can't read: entity not found",
+                    "line": null,
+                    "column": null,
+                    "io_error": "entity not found",
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// Agendas in the formats quex reads blocks from, to be pasted back into
+// notes: a markdown checklist per day and org headings with `SCHEDULED`
+// timestamps that org-agenda picks up.
+
+use super::{agenda, describe};
+use crate::{
+    calender::{CalendarKind, Recurrence},
+    Schedule, Schedules,
+};
+
+pub fn markdown(schedules: &Schedules) -> String {
+    let mut out = String::new();
+
+    for (day, group) in agenda::group_by_day(schedules) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        // no `— Today` but the year, the agenda outlives the day it was pasted on
+        out.push_str(&format!("## {}\n\n", markdown_title(day)));
+        for sch in group {
+            out.push_str(&format!("- [ ] {}\n", describe(sch)));
+        }
+    }
+
+    out
+}
+
+pub fn org(schedules: &Schedules) -> String {
+    schedules
+        .iter()
+        .map(|sch| {
+            let mut heading = format!(
+                "* TODO {}\n  SCHEDULED: <{}{}>\n",
+                sch.description,
+                org_timestamp(sch.julian_day_number),
+                org_repeater(sch)
+            );
+
+            // org-agenda shows the CATEGORY property next to the entry
+            if let Some(category) = &sch.category {
+                heading.push_str(&format!(
+                    "  :PROPERTIES:\n  :CATEGORY: {category}\n  :END:\n"
+                ));
+            }
+
+            heading
+        })
+        .collect()
+}
+
+/// `Wed, Jan 1, 2025`
+fn markdown_title(julian_day_number: i32) -> String {
+    match time::Date::from_julian_day(julian_day_number) {
+        Ok(date) => format!("{}, {}", agenda::day_title(julian_day_number), date.year()),
+        Err(_) => julian_day_number.to_string(),
+    }
+}
+
+/// ` +1y` for the yearly events, ` +1m` for the monthly ones, which quex
+/// reads back as `jan 1 2025*` and `d=1`. Org timestamps are Gregorian, so
+/// Ethiopian events aren't repeated.
+fn org_repeater(sch: &Schedule) -> &'static str {
+    if sch.calendar != CalendarKind::Gregorian {
+        return "";
+    }
+
+    match sch.recurrence {
+        Recurrence::Yearly | Recurrence::NamedYearly => " +1y",
+        Recurrence::Monthly => " +1m",
+        Recurrence::Once => "",
+    }
+}
+
+/// `2025-01-01 Wed`
+fn org_timestamp(julian_day_number: i32) -> String {
+    match time::Date::from_julian_day(julian_day_number) {
+        Ok(date) => {
+            let weekday: String = date.weekday().to_string().chars().take(3).collect();
+            format!("{date} {weekday}")
+        }
+        Err(_) => julian_day_number.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{markdown_title, org_timestamp};
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(org_timestamp(2460677), "2025-01-01 Wed");
+        assert_eq!(markdown_title(2460677), "Wed, Jan 1, 2025");
+    }
+}
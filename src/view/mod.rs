@@ -0,0 +1,106 @@
+mod agenda;
+mod cal;
+mod delimited;
+pub mod grid;
+mod html;
+mod json;
+mod markup;
+pub mod style;
+pub mod template;
+
+use std::{fs, io, io::stdout, path::Path};
+
+use crate::{cli::Format, filter::Window, QErrors, Schedule, Schedules};
+
+use grid::MonthGrid;
+use style::Palette;
+use template::Template;
+
+/// Settings used by some of the output formats.
+#[derive(Debug, Default)]
+pub struct ViewOptions {
+    pub template: Template,
+    pub palette: Palette,
+    /// Separate the weeks of `--format agenda`.
+    pub week_separators: bool,
+}
+
+/// The description followed by the category, if the schedule has one.
+fn describe(sch: &Schedule) -> String {
+    match &sch.category {
+        Some(category) => format!("{} [{}]", sch.description, category),
+        None => sch.description.clone(),
+    }
+}
+
+pub fn view_schedules(schedules: Schedules, format: &Format, options: &ViewOptions) {
+    match format {
+        Format::Json => println!("{}", json::format(&schedules)),
+        Format::Plain => schedules.iter().for_each(|sch| {
+            let line = match sch.diff {
+                0 => format!("Today; {}", describe(sch)),
+                1 => format!("Tomorrow; {}", describe(sch)),
+                -1 => format!("Yesterday; {}", describe(sch)),
+                _ => format!("{}; {}", sch.date, describe(sch)),
+            };
+
+            println!(
+                "{}",
+                options.palette.paint(options.palette.style(sch), &line)
+            );
+        }),
+        Format::Csv => print!("{}", delimited::format(&schedules, ',')),
+        Format::Tsv => print!("{}", delimited::format(&schedules, '\t')),
+        Format::Template => schedules
+            .iter()
+            .for_each(|sch| println!("{}", options.template.render(sch))),
+        Format::Jsonl => stop_on_broken_pipe(json::write_event_lines(&schedules, stdout().lock())),
+        Format::Agenda => print!(
+            "{}",
+            agenda::format(&schedules, options.week_separators, &options.palette)
+        ),
+        Format::Markdown => print!("{}", markup::markdown(&schedules)),
+        Format::Org => print!("{}", markup::org(&schedules)),
+    }
+}
+
+pub fn view_parse_errors(errors: QErrors, format: &Format) {
+    match format {
+        Format::Json => eprintln!("{}", json::format_errors(&errors)),
+        // errors share the event stream, each record is tagged with its type
+        Format::Jsonl => stop_on_broken_pipe(json::write_error_lines(&errors, stdout().lock())),
+        Format::Plain
+        | Format::Csv
+        | Format::Tsv
+        | Format::Template
+        | Format::Agenda
+        | Format::Markdown
+        | Format::Org => errors
+            .into_iter()
+            .for_each(|err| eprint!("{}", err.format())),
+    }
+}
+
+/// A reader that stops early, `quex --format jsonl | head -1`, ends the output
+/// rather than being an error.
+fn stop_on_broken_pipe(result: io::Result<()>) {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        result => result.unwrap(),
+    }
+}
+
+/// Prints the schedules and the errors as a single JSON document on stdout.
+pub fn view_envelope(schedules: Schedules, errors: QErrors, window: Window) {
+    println!("{}", json::envelope(&schedules, &errors, window));
+}
+
+/// Draws a month grid followed by the events of that month.
+pub fn view_calendar(grid: &MonthGrid, schedules: Schedules, options: &ViewOptions) {
+    print!("{}", cal::format(grid, &schedules, &options.palette));
+}
+
+/// Writes a self-contained HTML report of the schedules.
+pub fn export_html(schedules: &Schedules, out: &Path) -> io::Result<()> {
+    fs::write(out, html::format(schedules))
+}
@@ -0,0 +1,136 @@
+// ANSI styling of the terminal output.
+//
+// Today is highlighted, overdue events are dimmed and events of the next few
+// days are bold. On top of that the `[colors]` table of the config file gives
+// a color to every event carrying a `#tag` or coming from a file, e.g.
+//
+// [colors]
+// "#work" = "blue"
+// "birthdays.quex" = "magenta"
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::Schedule;
+
+/// How many days ahead an event is considered near and printed in bold.
+const NEAR_TERM: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn code(&self) -> u8 {
+        30 + *self as u8
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let color = match s.to_lowercase().as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            _ => return Err(format!("unknown color `{s}`")),
+        };
+
+        Ok(color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = vec![];
+
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.dim {
+            codes.push("2".to_string());
+        }
+        if self.reverse {
+            codes.push("7".to_string());
+        }
+        if let Some(color) = self.color {
+            codes.push(color.code().to_string());
+        }
+
+        if codes.is_empty() {
+            return text.to_string();
+        }
+
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+/// Decides the style of each event, a disabled palette never styles anything.
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    pub enabled: bool,
+    /// Colors keyed by `#tag` or by file name.
+    pub colors: HashMap<String, Color>,
+}
+
+impl Palette {
+    pub fn new(enabled: bool, colors: HashMap<String, Color>) -> Self {
+        Self { enabled, colors }
+    }
+
+    pub fn style(&self, sch: &Schedule) -> Style {
+        Style {
+            color: self.color(sch),
+            bold: (0..=NEAR_TERM).contains(&sch.diff),
+            dim: sch.diff < 0,
+            reverse: sch.diff == 0,
+        }
+    }
+
+    pub fn paint(&self, style: Style, text: &str) -> String {
+        match self.enabled {
+            true => style.paint(text),
+            false => text.to_string(),
+        }
+    }
+
+    fn color(&self, sch: &Schedule) -> Option<Color> {
+        let tag = sch
+            .description
+            .split_whitespace()
+            .filter(|word| word.starts_with('#'))
+            .find_map(|tag| self.colors.get(tag));
+
+        let file = || {
+            let path = sch.source_path.as_ref()?;
+            let name = path.file_name()?.to_string_lossy();
+
+            self.colors
+                .get(name.as_ref())
+                .or_else(|| self.colors.get(path.to_string_lossy().as_ref()))
+        };
+
+        tag.or_else(file).copied()
+    }
+}
@@ -0,0 +1,292 @@
+// User defined output, e.g. `--template '{iso} {diff:>4} {description}'`.
+//
+// A placeholder is a field name in braces, optionally followed by a format
+// spec after a colon: `{field:[[fill]align][width]}` where align is one of
+// `<`, `>` or `^`, the same as rust's `format!`. Literal braces are written
+// as `{{` and `}}`.
+
+use std::str::FromStr;
+
+use crate::Schedule;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Field(Field, Spec),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Description,
+    Date,
+    IsoDate,
+    JulianDayNumber,
+    Diff,
+    SourcePath,
+    LineNumber,
+    Calendar,
+    Recurrence,
+    Raw,
+    Category,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    width: usize,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+    #[error("unclosed placeholder, expected `}}`")]
+    UnclosedPlaceholder,
+    #[error("unmatched `}}`, use `}}}}` for a literal brace")]
+    UnmatchedBrace,
+    #[error("unknown placeholder `{0}`")]
+    UnknownField(String),
+    #[error("invalid format spec `{0}`, expected `[[fill]align][width]`")]
+    InvalidSpec(String),
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        let field = match name {
+            "description" => Field::Description,
+            "date" => Field::Date,
+            "iso" | "iso_date" => Field::IsoDate,
+            "jdn" | "julian_day_number" => Field::JulianDayNumber,
+            "diff" => Field::Diff,
+            "file" | "source_path" => Field::SourcePath,
+            "line" | "line_number" => Field::LineNumber,
+            "calendar" => Field::Calendar,
+            "recurrence" => Field::Recurrence,
+            "raw" => Field::Raw,
+            "category" => Field::Category,
+            _ => return None,
+        };
+
+        Some(field)
+    }
+
+    fn value(&self, sch: &Schedule) -> String {
+        match self {
+            Field::Description => sch.description.clone(),
+            Field::Date => sch.date.clone(),
+            Field::IsoDate => sch.iso_date(),
+            Field::JulianDayNumber => sch.julian_day_number.to_string(),
+            Field::Diff => sch.diff.to_string(),
+            Field::SourcePath => sch
+                .source_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            Field::LineNumber => sch.line_number.map(|l| l.to_string()).unwrap_or_default(),
+            Field::Calendar => sch.calendar.to_string(),
+            Field::Recurrence => sch.recurrence.to_string(),
+            Field::Raw => sch.raw.clone(),
+            Field::Category => sch.category.clone().unwrap_or_default(),
+        }
+    }
+
+    // numbers are right aligned by default, like `format!` does
+    fn default_align(&self) -> Align {
+        match self {
+            Field::JulianDayNumber | Field::Diff | Field::LineNumber => Align::Right,
+            _ => Align::Left,
+        }
+    }
+}
+
+impl Spec {
+    const NONE: Spec = Spec {
+        fill: ' ',
+        align: None,
+        width: 0,
+    };
+
+    fn parse(spec: &str) -> Result<Self, TemplateError> {
+        let invalid = || TemplateError::InvalidSpec(spec.to_string());
+        let align_of = |c: char| match c {
+            '<' => Some(Align::Left),
+            '>' => Some(Align::Right),
+            '^' => Some(Align::Center),
+            _ => None,
+        };
+
+        let mut chars = spec.chars();
+        let mut parsed = Spec::NONE;
+
+        let rest = match (chars.next(), chars.next()) {
+            (Some(fill), Some(a)) if align_of(a).is_some() => {
+                parsed.fill = fill;
+                parsed.align = align_of(a);
+                chars.as_str()
+            }
+            (Some(a), _) if align_of(a).is_some() => {
+                parsed.align = align_of(a);
+                &spec[a.len_utf8()..]
+            }
+            _ => spec,
+        };
+
+        if !rest.is_empty() {
+            parsed.width = rest.parse().map_err(|_| invalid())?;
+        }
+
+        Ok(parsed)
+    }
+
+    fn pad(&self, value: String, default: Align) -> String {
+        let len = value.chars().count();
+        if len >= self.width {
+            return value;
+        }
+
+        let padding = self.width - len;
+        let (left, right) = match self.align.unwrap_or(default) {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        };
+
+        let fill = |n| self.fill.to_string().repeat(n);
+        format!("{}{}{}", fill(left), value, fill(right))
+    }
+}
+
+impl Template {
+    pub fn render(&self, sch: &Schedule) -> String {
+        self.pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Literal(s) => s.clone(),
+                Piece::Field(field, spec) => spec.pad(field.value(sch), field.default_align()),
+            })
+            .collect()
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        "{date}; {description}".parse().unwrap()
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces = vec![];
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(TemplateError::UnclosedPlaceholder),
+                        }
+                    }
+
+                    let (name, spec) = match placeholder.split_once(':') {
+                        Some((name, spec)) => (name, Spec::parse(spec)?),
+                        None => (placeholder.as_str(), Spec::NONE),
+                    };
+
+                    let field = Field::from_name(name.trim())
+                        .ok_or_else(|| TemplateError::UnknownField(name.to_string()))?;
+
+                    if !literal.is_empty() {
+                        pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                    }
+                    pieces.push(Piece::Field(field, spec));
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+
+        Ok(Template { pieces })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::Template;
+    use crate::{
+        calender::{CalendarKind, Recurrence},
+        Schedule,
+    };
+
+    fn schedule() -> Schedule {
+        Schedule {
+            description: "Play soccer".to_string(),
+            julian_day_number: 2460677,
+            diff: 5,
+            date: "January 1, 2025".to_string(),
+            source_path: Some(PathBuf::from("notes.quex")),
+            line_number: Some(3),
+            calendar: CalendarKind::Gregorian,
+            recurrence: Recurrence::Once,
+            raw: "jan 1 2025, Play soccer".to_string(),
+            category: None,
+        }
+    }
+
+    #[test]
+    fn render_fields() {
+        let template: Template = "{iso} {diff:>4} {description} ({file}:{line}) {{{calendar}}}"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            template.render(&schedule()),
+            "2025-01-01    5 Play soccer (notes.quex:3) {gregorian}"
+        );
+    }
+
+    #[test]
+    fn padding() {
+        let template: Template = "[{description:*^15}|{diff:<3}|{line:3}]".parse().unwrap();
+        assert_eq!(template.render(&schedule()), "[**Play soccer**|5  |  3]");
+    }
+
+    #[test]
+    fn errors() {
+        assert!("{nope}".parse::<Template>().is_err());
+        assert!("{diff".parse::<Template>().is_err());
+        assert!("diff}".parse::<Template>().is_err());
+        assert!("{diff:>x}".parse::<Template>().is_err());
+    }
+}